@@ -0,0 +1,116 @@
+#[derive(Clone, Copy, PartialEq)]
+pub enum Palette {
+    Standard,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl Palette {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Standard => "standard",
+            Palette::Deuteranopia => "deuteranopia",
+            Palette::Protanopia => "protanopia",
+            Palette::Tritanopia => "tritanopia",
+        }
+    }
+
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            Palette::Standard => "palette-standard",
+            Palette::Deuteranopia => "palette-deuteranopia",
+            Palette::Protanopia => "palette-protanopia",
+            Palette::Tritanopia => "palette-tritanopia",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Palette> {
+        match name {
+            "standard" => Some(Palette::Standard),
+            "deuteranopia" => Some(Palette::Deuteranopia),
+            "protanopia" => Some(Palette::Protanopia),
+            "tritanopia" => Some(Palette::Tritanopia),
+            _ => None,
+        }
+    }
+
+    pub fn cycled(&self, step: i32) -> Palette {
+        const ALL: [Palette; 4] = [Palette::Standard, Palette::Deuteranopia, Palette::Protanopia, Palette::Tritanopia];
+        crate::settings::cycle(&ALL, *self, step)
+    }
+
+    // (special rock / warnings, shock waves / highlights), drawn from the
+    // Okabe-Ito set so each pair stays distinct for that colour deficiency
+    fn colors(&self) -> (&'static str, &'static str) {
+        match self {
+            Palette::Standard => ("#ff0080", "#00ffff"),
+            Palette::Deuteranopia => ("#e69f00", "#56b4e9"),
+            Palette::Protanopia => ("#f0e442", "#0072b2"),
+            Palette::Tritanopia => ("#d55e00", "#cc79a7"),
+        }
+    }
+
+    // Rock materials in ALL_MATERIALS order, avoiding the palette's own pair
+    // so a tinted rock can't be mistaken for a special one
+    fn material_colors(&self) -> [&'static str; 6] {
+        match self {
+            Palette::Standard => ["white", "#a8b8c8", "#b0e8ff", "#ff9a3c", "#d8a8ff", "#c8a070"],
+            Palette::Deuteranopia => ["white", "#a8b8c8", "#009e73", "#f0e442", "#cc79a7", "#d55e00"],
+            Palette::Protanopia => ["white", "#a8b8c8", "#56b4e9", "#e69f00", "#cc79a7", "#009e73"],
+            Palette::Tritanopia => ["white", "#a8b8c8", "#56b4e9", "#e69f00", "#009e73", "#f0e442"],
+        }
+    }
+}
+
+pub struct AccessibilitySettings {
+    pub reduced_flash: bool,
+    pub palette: Palette,
+    pub high_contrast: bool,
+    pub hud_scale: f64,
+    pub hazard_markers: bool,
+}
+
+pub const HUD_SCALE_MIN: f64 = 0.75;
+pub const HUD_SCALE_MAX: f64 = 2.0;
+
+impl AccessibilitySettings {
+    pub fn new() -> Self {
+        AccessibilitySettings {
+            reduced_flash: false,
+            palette: Palette::Standard,
+            high_contrast: false,
+            hud_scale: 1.0,
+            hazard_markers: false,
+        }
+    }
+
+    pub fn theme(&self) -> Theme {
+        let (special, highlight) = self.palette.colors();
+        // High contrast draws every material in white; their outlines still
+        // tell them apart
+        let material_colors = if self.high_contrast { ["white"; 6] } else { self.palette.material_colors() };
+        Theme {
+            special,
+            highlight,
+            material_colors,
+            line_scale: if self.high_contrast { 2.0 } else { 1.0 },
+            high_contrast: self.high_contrast,
+            reduced_flash: self.reduced_flash,
+            hazard_markers: self.hazard_markers,
+            hud_scale: self.hud_scale,
+        }
+    }
+}
+
+// Everything draw code needs to know about the player's accessibility choices
+pub struct Theme {
+    pub special: &'static str,
+    pub highlight: &'static str,
+    pub material_colors: [&'static str; 6],
+    pub line_scale: f64,
+    pub high_contrast: bool,
+    pub reduced_flash: bool,
+    pub hazard_markers: bool,
+    pub hud_scale: f64,
+}
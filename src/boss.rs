@@ -0,0 +1,397 @@
+use std::f64::consts::PI;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::accessibility::Theme;
+use crate::rng;
+use crate::weapon::ray_cast_circle;
+use crate::{wrap_delta, Vector, WORLD_HEIGHT, WORLD_WIDTH};
+
+// Weak points take this many times the damage of a normal hit
+const WEAK_POINT_MULTIPLIER: i32 = 3;
+
+// Constant pull on the ship during a gravity attack, kept below the ship's
+// thrust so it can always fly clear
+const PULL_STRENGTH: f64 = 0.05;
+const PULL_FRAMES: i32 = 120;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BossAttack {
+    // Throws out a pair of small rocks
+    SpawnRocks,
+    // A ring of shots in every direction
+    Volley,
+    // A tight spread of shots at the ship
+    AimedBurst,
+    // Drags the ship toward the boss for a couple of seconds
+    GravityPull,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BossShape {
+    Rock,
+    Hull,
+}
+
+struct SegmentDefinition {
+    x: f64,
+    y: f64,
+    radius: f64,
+    health: i32,
+    weak: bool,
+}
+
+struct PhaseDefinition {
+    // Cycled through in order, one every `interval` frames
+    attacks: &'static [BossAttack],
+    interval: i32,
+    speed: f64,
+}
+
+pub struct BossDefinition {
+    pub name: &'static str,
+    pub label_key: &'static str,
+    // Reward for finishing the boss off, on top of every segment's points
+    pub score: i32,
+    pub segment_score: i32,
+    shape: BossShape,
+    spin: f64,
+    segments: &'static [SegmentDefinition],
+    // One phase per third of the boss's health
+    phases: [PhaseDefinition; 3],
+}
+
+const fn segment(x: f64, y: f64, radius: f64, health: i32, weak: bool) -> SegmentDefinition {
+    SegmentDefinition { x, y, radius, health, weak }
+}
+
+// A giant rock whose core is the weak point, shielded by a ring of chunks
+const TITAN: BossDefinition = BossDefinition {
+    name: "titan",
+    label_key: "boss-titan",
+    score: 5000,
+    segment_score: 100,
+    shape: BossShape::Rock,
+    spin: 0.01,
+    segments: &[
+        segment(0.0, 0.0, 28.0, 10, true),
+        segment(48.0, 0.0, 22.0, 4, false),
+        segment(33.9, 33.9, 22.0, 4, false),
+        segment(0.0, 48.0, 22.0, 4, false),
+        segment(-33.9, 33.9, 22.0, 4, false),
+        segment(-48.0, 0.0, 22.0, 4, false),
+        segment(-33.9, -33.9, 22.0, 4, false),
+        segment(0.0, -48.0, 22.0, 4, false),
+        segment(33.9, -33.9, 22.0, 4, false),
+    ],
+    phases: [
+        PhaseDefinition { attacks: &[BossAttack::SpawnRocks, BossAttack::Volley], interval: 180, speed: 0.6 },
+        PhaseDefinition {
+            attacks: &[BossAttack::Volley, BossAttack::GravityPull, BossAttack::SpawnRocks],
+            interval: 150,
+            speed: 0.8,
+        },
+        PhaseDefinition {
+            attacks: &[BossAttack::Volley, BossAttack::AimedBurst, BossAttack::GravityPull, BossAttack::SpawnRocks],
+            interval: 110,
+            speed: 1.0,
+        },
+    ],
+};
+
+// A wide ship with its bridge exposed on top
+const MOTHERSHIP: BossDefinition = BossDefinition {
+    name: "mothership",
+    label_key: "boss-mothership",
+    score: 6000,
+    segment_score: 150,
+    shape: BossShape::Hull,
+    spin: 0.0,
+    segments: &[
+        segment(0.0, -22.0, 16.0, 10, true),
+        segment(-30.0, 6.0, 20.0, 5, false),
+        segment(0.0, 12.0, 20.0, 5, false),
+        segment(30.0, 6.0, 20.0, 5, false),
+        segment(-64.0, 10.0, 16.0, 4, false),
+        segment(64.0, 10.0, 16.0, 4, false),
+        segment(-44.0, -18.0, 12.0, 3, false),
+        segment(44.0, -18.0, 12.0, 3, false),
+    ],
+    phases: [
+        PhaseDefinition { attacks: &[BossAttack::AimedBurst, BossAttack::Volley], interval: 150, speed: 1.0 },
+        PhaseDefinition {
+            attacks: &[BossAttack::AimedBurst, BossAttack::SpawnRocks, BossAttack::Volley],
+            interval: 120,
+            speed: 1.3,
+        },
+        PhaseDefinition {
+            attacks: &[BossAttack::AimedBurst, BossAttack::GravityPull, BossAttack::Volley, BossAttack::AimedBurst],
+            interval: 90,
+            speed: 1.6,
+        },
+    ],
+};
+
+// Boss levels take these in turn
+pub const BOSSES: [&BossDefinition; 2] = [&TITAN, &MOTHERSHIP];
+
+struct Segment {
+    health: i32,
+    // Frames left of the flash after a hit
+    flash: i32,
+}
+
+pub struct Boss {
+    pub definition: &'static BossDefinition,
+    pub position: Vector,
+    velocity: Vector,
+    rotation: f64,
+    segments: Vec<Segment>,
+    pub phase: usize,
+    attack_timer: i32,
+    next_attack: usize,
+    turn_timer: i32,
+    pull_timer: i32,
+}
+
+impl Boss {
+    // Appears in the upper part of the screen, clear of the ship at the centre
+    pub fn new(definition: &'static BossDefinition) -> Self {
+        Boss {
+            definition,
+            position: Vector { x: WORLD_WIDTH / 2.0, y: 110.0 },
+            velocity: Vector { x: 0.0, y: 0.0 },
+            rotation: 0.0,
+            segments: definition.segments.iter().map(|segment| Segment { health: segment.health, flash: 0 }).collect(),
+            phase: 0,
+            attack_timer: definition.phases[0].interval,
+            next_attack: 0,
+            turn_timer: 0,
+            pull_timer: 0,
+        }
+    }
+
+    pub fn max_health(&self) -> i32 {
+        self.definition.segments.iter().map(|segment| segment.health).sum()
+    }
+
+    pub fn health(&self) -> i32 {
+        self.segments.iter().map(|segment| segment.health).sum()
+    }
+
+    pub fn health_fraction(&self) -> f64 {
+        self.health() as f64 / self.max_health() as f64
+    }
+
+    pub fn is_defeated(&self) -> bool {
+        self.health() <= 0
+    }
+
+    // Outermost reach of the boss, for spawning shots clear of it
+    pub fn reach(&self) -> f64 {
+        self.definition
+            .segments
+            .iter()
+            .map(|segment| (segment.x * segment.x + segment.y * segment.y).sqrt() + segment.radius)
+            .fold(0.0, f64::max)
+    }
+
+    pub fn segment_center(&self, index: usize) -> Vector {
+        let shape = &self.definition.segments[index];
+        let (sin, cos) = self.rotation.sin_cos();
+        Vector {
+            x: (self.position.x + shape.x * cos - shape.y * sin).rem_euclid(WORLD_WIDTH),
+            y: (self.position.y + shape.x * sin + shape.y * cos).rem_euclid(WORLD_HEIGHT),
+        }
+    }
+
+    // Centre and radius of each segment still standing
+    pub fn segments(&self) -> Vec<(usize, Vector, f64)> {
+        (0..self.segments.len())
+            .filter(|&index| self.segments[index].health > 0)
+            .map(|index| (index, self.segment_center(index), self.definition.segments[index].radius))
+            .collect()
+    }
+
+    // Segment hit by a body of radius `size` at `position`. Where segments
+    // overlap the outermost takes the hit, so outer armour shields the core.
+    pub fn hit_test(&self, position: &Vector, size: f64) -> Option<usize> {
+        let depth = |index: usize| {
+            let shape = &self.definition.segments[index];
+            (shape.x * shape.x + shape.y * shape.y).sqrt()
+        };
+        self.segments()
+            .into_iter()
+            .filter(|(_, center, radius)| {
+                let delta = wrap_delta(position, center);
+                (delta.x * delta.x + delta.y * delta.y).sqrt() < radius + size
+            })
+            .map(|(index, _, _)| index)
+            .max_by(|a, b| depth(*a).total_cmp(&depth(*b)))
+    }
+
+    pub fn ray_hit(&self, origin: &Vector, direction: &Vector, range: f64) -> Option<(usize, f64)> {
+        self.segments()
+            .into_iter()
+            .filter_map(|(index, center, radius)| {
+                ray_cast_circle(origin, direction, range, &center, radius).map(|distance| (index, distance))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    // Returns the centre and radius of the segment if the hit broke it
+    pub fn damage(&mut self, index: usize, hits: i32) -> Option<(Vector, f64)> {
+        let shape = &self.definition.segments[index];
+        let amount = if shape.weak { hits * WEAK_POINT_MULTIPLIER } else { hits };
+        let segment = &mut self.segments[index];
+        if segment.health <= 0 {
+            return None;
+        }
+        segment.health = (segment.health - amount).max(0);
+        segment.flash = 6;
+        if segment.health > 0 {
+            return None;
+        }
+        Some((self.segment_center(index), shape.radius))
+    }
+
+    // Moves to the next phase once health drops past its threshold; returns
+    // true if it did
+    pub fn advance_phase(&mut self) -> bool {
+        let fraction = self.health_fraction();
+        let phase = if fraction > 2.0 / 3.0 {
+            0
+        } else if fraction > 1.0 / 3.0 {
+            1
+        } else {
+            2
+        };
+        if phase <= self.phase {
+            return false;
+        }
+        self.phase = phase;
+        self.next_attack = 0;
+        self.attack_timer = self.definition.phases[phase].interval;
+        true
+    }
+
+    pub fn pulling(&self) -> bool {
+        self.pull_timer > 0
+    }
+
+    pub fn start_pull(&mut self) {
+        self.pull_timer = PULL_FRAMES;
+    }
+
+    pub fn pull(&self, position: &Vector) -> Vector {
+        let delta = wrap_delta(position, &self.position);
+        let distance = (delta.x * delta.x + delta.y * delta.y).sqrt().max(1.0);
+        Vector { x: delta.x / distance * PULL_STRENGTH, y: delta.y / distance * PULL_STRENGTH }
+    }
+
+    // Drifts, turns and counts down to the next attack, which is returned
+    // when it is due. `time_scale` slows it while time is dilated.
+    pub fn update(&mut self, time_scale: f64) -> Option<BossAttack> {
+        let phase = &self.definition.phases[self.phase];
+
+        // Wander: pick a new heading every few seconds
+        self.turn_timer -= 1;
+        if self.turn_timer <= 0 {
+            let heading = rng::random() * 2.0 * PI;
+            self.velocity = Vector { x: heading.cos() * phase.speed, y: heading.sin() * phase.speed };
+            self.turn_timer = 180 + (rng::random() * 120.0) as i32;
+        }
+        self.position.x = (self.position.x + self.velocity.x * time_scale).rem_euclid(WORLD_WIDTH);
+        self.position.y = (self.position.y + self.velocity.y * time_scale).rem_euclid(WORLD_HEIGHT);
+        self.rotation += self.definition.spin * time_scale;
+
+        for segment in &mut self.segments {
+            segment.flash = (segment.flash - 1).max(0);
+        }
+        self.pull_timer = (self.pull_timer - 1).max(0);
+
+        self.attack_timer -= 1;
+        if self.attack_timer > 0 {
+            return None;
+        }
+        self.attack_timer = phase.interval;
+        let attack = phase.attacks[self.next_attack % phase.attacks.len()];
+        self.next_attack += 1;
+        Some(attack)
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+        ctx.save();
+        ctx.set_line_width(theme.line_scale);
+
+        // Rings closing in on the boss while it pulls
+        if self.pulling() && !theme.reduced_flash {
+            ctx.set_stroke_style_str(theme.highlight);
+            for ring in 0..3 {
+                let progress = ((self.pull_timer + ring * 20) % 60) as f64 / 60.0;
+                ctx.set_global_alpha(0.5 * (1.0 - progress));
+                ctx.begin_path();
+                ctx.arc(self.position.x, self.position.y, self.reach() + 120.0 * progress, 0.0, 2.0 * PI).unwrap();
+                ctx.stroke();
+            }
+            ctx.set_global_alpha(1.0);
+        }
+
+        for (index, center, radius) in self.segments() {
+            let shape = &self.definition.segments[index];
+            let flashing = self.segments[index].flash > 0 && !theme.reduced_flash;
+            let color = if flashing {
+                "white"
+            } else if shape.weak {
+                theme.special
+            } else {
+                "#c0c0c0"
+            };
+            ctx.set_stroke_style_str(color);
+            ctx.set_line_width(if shape.weak { 2.0 * theme.line_scale } else { theme.line_scale });
+
+            ctx.begin_path();
+            match self.definition.shape {
+                // Lumpy chunks
+                BossShape::Rock => {
+                    for corner in 0..9 {
+                        let angle = self.rotation + corner as f64 * 2.0 * PI / 9.0;
+                        let bump = 0.85 + 0.15 * ((index * 7 + corner * 3) % 5) as f64 / 4.0;
+                        let (x, y) = (center.x + angle.cos() * radius * bump, center.y + angle.sin() * radius * bump);
+                        if corner == 0 {
+                            ctx.move_to(x, y);
+                        } else {
+                            ctx.line_to(x, y);
+                        }
+                    }
+                }
+                // Armour plates
+                BossShape::Hull => {
+                    for corner in 0..6 {
+                        let angle = self.rotation + corner as f64 * PI / 3.0 + PI / 6.0;
+                        let (x, y) = (center.x + angle.cos() * radius, center.y + angle.sin() * radius);
+                        if corner == 0 {
+                            ctx.move_to(x, y);
+                        } else {
+                            ctx.line_to(x, y);
+                        }
+                    }
+                }
+            }
+            ctx.close_path();
+            ctx.stroke();
+
+            // Crosshair on weak points so they read by shape, not only by colour
+            if shape.weak {
+                ctx.begin_path();
+                ctx.arc(center.x, center.y, radius * 0.4, 0.0, 2.0 * PI).unwrap();
+                ctx.move_to(center.x - radius * 0.7, center.y);
+                ctx.line_to(center.x + radius * 0.7, center.y);
+                ctx.move_to(center.x, center.y - radius * 0.7);
+                ctx.line_to(center.x, center.y + radius * 0.7);
+                ctx.stroke();
+            }
+        }
+
+        ctx.restore();
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{wrap_delta, Asteroid, Vector, WORLD_HEIGHT, WORLD_WIDTH};
+
+pub struct CollisionSettings {
+    // Rocks pass through each other unless this is on
+    pub enabled: bool,
+    // Share of the closing speed kept after a bounce: 1.0 is perfectly
+    // elastic, 0.0 leaves the two rocks moving together
+    pub restitution: f64,
+}
+
+impl CollisionSettings {
+    pub fn new() -> Self {
+        CollisionSettings { enabled: false, restitution: 0.9 }
+    }
+}
+
+// Grid cells must be at least as wide as the largest contact distance (two
+// full-size rocks), so every touching pair sits in neighbouring cells
+const MIN_CELL_SIZE: f64 = 80.0;
+
+// Bounce every touching pair of rocks off each other. A wrapping grid keeps
+// this close to linear in the number of rocks, so a few hundred stay cheap.
+pub fn resolve_asteroid_collisions(asteroids: &mut [Asteroid], restitution: f64) {
+    if asteroids.len() < 2 {
+        return;
+    }
+
+    let columns = ((WORLD_WIDTH / MIN_CELL_SIZE).floor() as usize).max(1);
+    let rows = ((WORLD_HEIGHT / MIN_CELL_SIZE).floor() as usize).max(1);
+    let cell_of = |position: &Vector| {
+        let column = ((position.x.rem_euclid(WORLD_WIDTH) / WORLD_WIDTH * columns as f64) as usize).min(columns - 1);
+        let row = ((position.y.rem_euclid(WORLD_HEIGHT) / WORLD_HEIGHT * rows as f64) as usize).min(rows - 1);
+        (column, row)
+    };
+
+    let mut cells: Vec<Vec<usize>> = vec![Vec::new(); columns * rows];
+    for (index, asteroid) in asteroids.iter().enumerate() {
+        let (column, row) = cell_of(&asteroid.position);
+        cells[row * columns + column].push(index);
+    }
+
+    let mut neighbours: Vec<usize> = Vec::with_capacity(9);
+    for first in 0..asteroids.len() {
+        let (column, row) = cell_of(&asteroids[first].position);
+
+        // The grid wraps like the world; a narrow grid can reach the same
+        // cell from both sides, so skip repeats
+        neighbours.clear();
+        for dy in [rows - 1, 0, 1] {
+            for dx in [columns - 1, 0, 1] {
+                let cell = ((row + dy) % rows) * columns + (column + dx) % columns;
+                if !neighbours.contains(&cell) {
+                    neighbours.push(cell);
+                }
+            }
+        }
+
+        for &cell in &neighbours {
+            for &second in &cells[cell] {
+                if second > first {
+                    collide(asteroids, first, second, restitution);
+                }
+            }
+        }
+    }
+}
+
+fn collide(asteroids: &mut [Asteroid], first: usize, second: usize, restitution: f64) {
+    let (head, tail) = asteroids.split_at_mut(second);
+    let (a, b) = (&mut head[first], &mut tail[0]);
+
+    let delta = wrap_delta(&a.position, &b.position);
+    let distance = (delta.x * delta.x + delta.y * delta.y).sqrt();
+    let contact = a.size + b.size;
+    if distance >= contact {
+        return;
+    }
+    let normal = if distance > 0.0 {
+        Vector { x: delta.x / distance, y: delta.y / distance }
+    } else {
+        Vector { x: 1.0, y: 0.0 }
+    };
+
+    // Push the pair apart along the normal, the lighter rock moving further
+    let (inverse_a, inverse_b) = (1.0 / a.mass(), 1.0 / b.mass());
+    let inverse_total = inverse_a + inverse_b;
+    let overlap = contact - distance;
+    a.position.x = (a.position.x - normal.x * overlap * inverse_a / inverse_total).rem_euclid(WORLD_WIDTH);
+    a.position.y = (a.position.y - normal.y * overlap * inverse_a / inverse_total).rem_euclid(WORLD_HEIGHT);
+    b.position.x = (b.position.x + normal.x * overlap * inverse_b / inverse_total).rem_euclid(WORLD_WIDTH);
+    b.position.y = (b.position.y + normal.y * overlap * inverse_b / inverse_total).rem_euclid(WORLD_HEIGHT);
+
+    // Rocks already moving apart keep their velocities
+    let closing = (b.velocity.x - a.velocity.x) * normal.x + (b.velocity.y - a.velocity.y) * normal.y;
+    if closing >= 0.0 {
+        return;
+    }
+    let impulse = -(1.0 + restitution) * closing / inverse_total;
+    a.velocity.x -= normal.x * impulse * inverse_a;
+    a.velocity.y -= normal.y * impulse * inverse_a;
+    b.velocity.x += normal.x * impulse * inverse_b;
+    b.velocity.y += normal.y * impulse * inverse_b;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wrap_distance;
+
+    fn rock(x: f64, y: f64, vx: f64, vy: f64) -> Asteroid {
+        let mut rock = Asteroid::new(x, y, 20.0);
+        rock.velocity = Vector { x: vx, y: vy };
+        rock
+    }
+
+    fn velocities(rocks: &[Asteroid]) -> Vec<(f64, f64)> {
+        rocks.iter().map(|rock| (rock.velocity.x, rock.velocity.y)).collect()
+    }
+
+    #[test]
+    fn rocks_bounce_across_the_screen_edge() {
+        let mut rocks = [rock(WORLD_WIDTH - 5.0, 300.0, 1.0, 0.0), rock(5.0, 300.0, -1.0, 0.0)];
+        resolve_asteroid_collisions(&mut rocks, 1.0);
+        assert_eq!(velocities(&rocks), [(-1.0, 0.0), (1.0, 0.0)]);
+
+        // Pushed apart to touching, and still inside the world
+        assert!((wrap_distance(&rocks[0].position, &rocks[1].position) - 40.0).abs() < 1e-9);
+        for rock in &rocks {
+            assert!((0.0..WORLD_WIDTH).contains(&rock.position.x));
+        }
+    }
+
+    #[test]
+    fn rocks_bounce_across_the_corner() {
+        let mut rocks = [rock(WORLD_WIDTH - 5.0, WORLD_HEIGHT - 5.0, 1.0, 1.0), rock(5.0, 5.0, -1.0, -1.0)];
+        resolve_asteroid_collisions(&mut rocks, 1.0);
+        let after = velocities(&rocks);
+        assert!(after[0].0 < 0.0 && after[0].1 < 0.0);
+        assert!(after[1].0 > 0.0 && after[1].1 > 0.0);
+    }
+
+    #[test]
+    fn distant_or_separating_rocks_are_left_alone() {
+        // Same cell row but on opposite sides of the screen
+        let mut rocks = [rock(WORLD_WIDTH / 2.0 - 5.0, 300.0, 1.0, 0.0), rock(5.0, 300.0, -1.0, 0.0)];
+        resolve_asteroid_collisions(&mut rocks, 1.0);
+        assert_eq!(velocities(&rocks), [(1.0, 0.0), (-1.0, 0.0)]);
+
+        let mut rocks = [rock(WORLD_WIDTH - 5.0, 300.0, -1.0, 0.0), rock(5.0, 300.0, 1.0, 0.0)];
+        resolve_asteroid_collisions(&mut rocks, 1.0);
+        assert_eq!(velocities(&rocks), [(-1.0, 0.0), (1.0, 0.0)]);
+        assert!(wrap_distance(&rocks[0].position, &rocks[1].position) >= 40.0 - 1e-9);
+    }
+}
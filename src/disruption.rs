@@ -0,0 +1,485 @@
+use std::f64::consts::PI;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::accessibility::Theme;
+use crate::rng;
+use crate::{wrap_delta, Vector, WORLD_HEIGHT, WORLD_WIDTH};
+
+pub const GRAVITY_PULSE_RADIUS: f64 = 250.0;
+const GRAVITY_PULSE_STRENGTH: f64 = 0.08;
+pub const TIME_DILATION_SCALE: f64 = 0.4;
+pub const BLACKOUT_RADIUS: f64 = 120.0;
+// Radians per frame that a magnetic field bends a bullet's path
+pub const MAGNETIC_TURN: f64 = 0.03;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DisruptionType {
+    None,
+    Disabled,
+    Scrambled,
+    Uncontrollable,
+    GravityPulse,
+    TimeDilation,
+    Blackout,
+    MagneticField,
+    MirrorWorld,
+}
+
+// Everything a special rock can trigger
+const ALL_DISRUPTIONS: [DisruptionType; 8] = [
+    DisruptionType::Disabled,
+    DisruptionType::Scrambled,
+    DisruptionType::Uncontrollable,
+    DisruptionType::GravityPulse,
+    DisruptionType::TimeDilation,
+    DisruptionType::Blackout,
+    DisruptionType::MagneticField,
+    DisruptionType::MirrorWorld,
+];
+
+impl DisruptionType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisruptionType::None => "none",
+            DisruptionType::Disabled => "disabled",
+            DisruptionType::Scrambled => "scrambled",
+            DisruptionType::Uncontrollable => "uncontrollable",
+            DisruptionType::GravityPulse => "gravity_pulse",
+            DisruptionType::TimeDilation => "time_dilation",
+            DisruptionType::Blackout => "blackout",
+            DisruptionType::MagneticField => "magnetic_field",
+            DisruptionType::MirrorWorld => "mirror_world",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<DisruptionType> {
+        ALL_DISRUPTIONS.iter().copied().find(|kind| kind.name() == name)
+    }
+
+    pub fn label_key(&self) -> Option<&'static str> {
+        match self {
+            DisruptionType::None => None,
+            DisruptionType::Disabled => Some("disruption-disabled"),
+            DisruptionType::Scrambled => Some("disruption-scrambled"),
+            DisruptionType::Uncontrollable => Some("disruption-unstable"),
+            DisruptionType::GravityPulse => Some("disruption-gravity"),
+            DisruptionType::TimeDilation => Some("disruption-time"),
+            DisruptionType::Blackout => Some("disruption-blackout"),
+            DisruptionType::MagneticField => Some("disruption-magnetic"),
+            DisruptionType::MirrorWorld => Some("disruption-mirror"),
+        }
+    }
+
+    pub fn sound_id(&self) -> Option<&'static str> {
+        match self {
+            DisruptionType::GravityPulse => Some("gravity-pulse-sound"),
+            DisruptionType::TimeDilation => Some("time-dilation-sound"),
+            DisruptionType::Blackout => Some("blackout-sound"),
+            DisruptionType::MagneticField => Some("magnetic-sound"),
+            DisruptionType::MirrorWorld => Some("mirror-sound"),
+            _ => None,
+        }
+    }
+
+    // Frames the effect lasts at 60 FPS
+    pub fn duration(&self) -> i32 {
+        match self {
+            DisruptionType::None => 0,
+            DisruptionType::GravityPulse => 90,
+            DisruptionType::TimeDilation | DisruptionType::MagneticField => 240,
+            DisruptionType::Blackout | DisruptionType::MirrorWorld => 300,
+            _ => 120,
+        }
+    }
+}
+
+pub struct DisruptionSettings {
+    // Relative chance of each disruption; a weight of 0 turns it off
+    weights: [f64; 8],
+}
+
+impl DisruptionSettings {
+    pub fn new() -> Self {
+        DisruptionSettings { weights: [1.0; 8] }
+    }
+
+    // Returns false for `DisruptionType::None`, which can't be weighted
+    pub fn set_weight(&mut self, kind: DisruptionType, weight: f64) -> bool {
+        match ALL_DISRUPTIONS.iter().position(|candidate| *candidate == kind) {
+            Some(index) => {
+                self.weights[index] = weight.max(0.0);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn choose(&self) -> DisruptionType {
+        let total: f64 = self.weights.iter().sum();
+        if total <= 0.0 {
+            return DisruptionType::None;
+        }
+        let mut roll = rng::random() * total;
+        for (kind, weight) in ALL_DISRUPTIONS.iter().zip(self.weights) {
+            roll -= weight;
+            if roll < 0.0 {
+                return *kind;
+            }
+        }
+        ALL_DISRUPTIONS[ALL_DISRUPTIONS.len() - 1]
+    }
+}
+
+// At most this many disruptions run at once; later ones wait their turn
+pub const MAX_ACTIVE_DISRUPTIONS: usize = 3;
+
+pub struct ActiveDisruption {
+    pub kind: DisruptionType,
+    pub remaining: i32,
+}
+
+impl ActiveDisruption {
+    pub fn fraction_left(&self) -> f64 {
+        self.remaining as f64 / self.kind.duration().max(1) as f64
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Trigger {
+    Started,
+    // Already running; its timer was restarted
+    Refreshed,
+    Queued,
+}
+
+// Every disruption affecting the ship. Different kinds stack and all apply
+// together; hitting a kind that is already running restarts its timer.
+pub struct Disruptions {
+    pub active: Vec<ActiveDisruption>,
+    queued: Vec<(DisruptionType, Vector)>,
+    pub uncontrollable_force: Vector,
+    // Where the special rock behind the latest gravity pulse broke up
+    pub gravity_origin: Vector,
+    // Which way a magnetic field bends bullets, +1 or -1
+    pub magnetic_direction: f64,
+}
+
+impl Disruptions {
+    pub fn new() -> Self {
+        Disruptions {
+            active: Vec::new(),
+            queued: Vec::new(),
+            uncontrollable_force: Vector { x: 0.0, y: 0.0 },
+            gravity_origin: Vector { x: 0.0, y: 0.0 },
+            magnetic_direction: 1.0,
+        }
+    }
+
+    pub fn has(&self, kind: DisruptionType) -> bool {
+        self.active.iter().any(|effect| effect.kind == kind)
+    }
+
+    // `origin` is where the special rock broke up
+    pub fn trigger(&mut self, kind: DisruptionType, origin: &Vector) -> Trigger {
+        if let Some(effect) = self.active.iter_mut().find(|effect| effect.kind == kind) {
+            effect.remaining = kind.duration();
+            self.prepare(kind, origin);
+            return Trigger::Refreshed;
+        }
+        if self.active.len() >= MAX_ACTIVE_DISRUPTIONS {
+            if !self.queued.iter().any(|(queued, _)| *queued == kind) {
+                self.queued.push((kind, origin.clone()));
+            }
+            return Trigger::Queued;
+        }
+        self.active.push(ActiveDisruption { kind, remaining: kind.duration() });
+        self.prepare(kind, origin);
+        Trigger::Started
+    }
+
+    fn prepare(&mut self, kind: DisruptionType, origin: &Vector) {
+        match kind {
+            DisruptionType::Uncontrollable => {
+                // Random uncontrollable force
+                let angle = rng::random() * 2.0 * PI;
+                self.uncontrollable_force = Vector { x: 0.2 * angle.sin(), y: -0.2 * angle.cos() };
+            }
+            DisruptionType::GravityPulse => self.gravity_origin = origin.clone(),
+            DisruptionType::MagneticField => {
+                self.magnetic_direction = if rng::random() < 0.5 { -1.0 } else { 1.0 };
+            }
+            _ => {}
+        }
+    }
+
+    // Counts every effect down. Returns the kinds that ended this frame and
+    // the queued kinds that took their places.
+    pub fn tick(&mut self) -> (Vec<DisruptionType>, Vec<DisruptionType>) {
+        for effect in &mut self.active {
+            effect.remaining -= 1;
+        }
+        let ended: Vec<DisruptionType> =
+            self.active.iter().filter(|effect| effect.remaining <= 0).map(|effect| effect.kind).collect();
+        self.active.retain(|effect| effect.remaining > 0);
+
+        let mut started = Vec::new();
+        while self.active.len() < MAX_ACTIVE_DISRUPTIONS && !self.queued.is_empty() {
+            let (kind, origin) = self.queued.remove(0);
+            if self.trigger(kind, &origin) == Trigger::Started {
+                started.push(kind);
+            }
+        }
+        (ended, started)
+    }
+
+    // Drops every effect, queued ones included; returns those that were running
+    pub fn clear(&mut self) -> Vec<DisruptionType> {
+        self.queued.clear();
+        self.active.drain(..).map(|effect| effect.kind).collect()
+    }
+
+    // A small glyph for each effect, circling the ship
+    pub fn draw_cues(&self, ctx: &CanvasRenderingContext2d, center: &Vector, theme: &Theme) {
+        ctx.save();
+        ctx.set_stroke_style_str(theme.special);
+        ctx.set_line_width(theme.line_scale);
+        let count = self.active.len() as f64;
+        for (index, effect) in self.active.iter().enumerate() {
+            let orbit = index as f64 * 2.0 * PI / count + effect.remaining as f64 * 0.03;
+            ctx.save();
+            ctx.translate(center.x + 34.0 * orbit.sin(), center.y - 34.0 * orbit.cos()).unwrap();
+            ctx.begin_path();
+            match effect.kind {
+                DisruptionType::Disabled => {
+                    ctx.move_to(-4.0, -4.0);
+                    ctx.line_to(4.0, 4.0);
+                    ctx.move_to(4.0, -4.0);
+                    ctx.line_to(-4.0, 4.0);
+                }
+                DisruptionType::Scrambled => {
+                    ctx.move_to(-5.0, 0.0);
+                    ctx.line_to(-2.5, -4.0);
+                    ctx.line_to(0.0, 4.0);
+                    ctx.line_to(2.5, -4.0);
+                    ctx.line_to(5.0, 0.0);
+                }
+                DisruptionType::Uncontrollable => {
+                    // Arrow along the drift
+                    let force = &self.uncontrollable_force;
+                    ctx.rotate(force.x.atan2(-force.y)).unwrap();
+                    ctx.move_to(0.0, 5.0);
+                    ctx.line_to(0.0, -5.0);
+                    ctx.move_to(-3.0, -2.0);
+                    ctx.line_to(0.0, -5.0);
+                    ctx.line_to(3.0, -2.0);
+                }
+                DisruptionType::GravityPulse => {
+                    ctx.arc(0.0, 0.0, 5.0, 0.0, 2.0 * PI).unwrap();
+                    ctx.move_to(2.0, 0.0);
+                    ctx.arc(0.0, 0.0, 2.0, 0.0, 2.0 * PI).unwrap();
+                }
+                DisruptionType::TimeDilation => {
+                    let hand = effect.remaining as f64 * 0.05;
+                    ctx.arc(0.0, 0.0, 5.0, 0.0, 2.0 * PI).unwrap();
+                    ctx.move_to(0.0, 0.0);
+                    ctx.line_to(4.0 * hand.sin(), -4.0 * hand.cos());
+                }
+                DisruptionType::Blackout => {
+                    ctx.arc(0.0, 0.0, 5.0, 0.0, 2.0 * PI).unwrap();
+                    ctx.move_to(-5.0, 0.0);
+                    ctx.line_to(5.0, 0.0);
+                }
+                DisruptionType::MagneticField => {
+                    // Horseshoe magnet
+                    ctx.move_to(-4.0, -5.0);
+                    ctx.line_to(-4.0, 1.0);
+                    ctx.arc_with_anticlockwise(0.0, 1.0, 4.0, PI, 0.0, true).unwrap();
+                    ctx.line_to(4.0, -5.0);
+                }
+                DisruptionType::MirrorWorld => {
+                    ctx.move_to(-1.5, -5.0);
+                    ctx.line_to(-6.0, 0.0);
+                    ctx.line_to(-1.5, 5.0);
+                    ctx.move_to(1.5, -5.0);
+                    ctx.line_to(6.0, 0.0);
+                    ctx.line_to(1.5, 5.0);
+                }
+                DisruptionType::None => {}
+            }
+            ctx.stroke();
+            ctx.restore();
+        }
+        ctx.restore();
+    }
+}
+
+// Velocity change that pulls a body at `position` toward `origin`, strongest
+// close in and fading to nothing at the edge of the pulse
+pub fn gravity_pull(origin: &Vector, position: &Vector) -> Vector {
+    let delta = wrap_delta(position, origin);
+    let distance = (delta.x * delta.x + delta.y * delta.y).sqrt();
+    if !(1.0..GRAVITY_PULSE_RADIUS).contains(&distance) {
+        return Vector { x: 0.0, y: 0.0 };
+    }
+    let strength = GRAVITY_PULSE_STRENGTH * (1.0 - distance / GRAVITY_PULSE_RADIUS);
+    Vector { x: delta.x / distance * strength, y: delta.y / distance * strength }
+}
+
+// Rings closing in on the impact point; `timer` counts down the effect
+pub fn draw_gravity_pulse(ctx: &CanvasRenderingContext2d, origin: &Vector, timer: i32, theme: &Theme) {
+    ctx.save();
+    ctx.set_stroke_style_str(theme.special);
+    ctx.set_line_width(theme.line_scale);
+    for ring in 0..3 {
+        let phase = ((timer + ring * 10) % 30) as f64 / 30.0;
+        ctx.set_global_alpha(0.6 * (1.0 - phase));
+        ctx.begin_path();
+        ctx.arc(origin.x, origin.y, GRAVITY_PULSE_RADIUS * phase, 0.0, 2.0 * PI).unwrap();
+        ctx.stroke();
+    }
+    ctx.restore();
+}
+
+// Faint wavy field lines drifting across the screen
+pub fn draw_magnetic_field(ctx: &CanvasRenderingContext2d, timer: i32, direction: f64, theme: &Theme) {
+    ctx.save();
+    ctx.set_stroke_style_str(theme.highlight);
+    ctx.set_global_alpha(0.15);
+    ctx.set_line_width(theme.line_scale);
+    let drift = timer as f64 * 0.05 * direction;
+    for line in 0..8 {
+        let base_y = 40.0 + line as f64 * 75.0;
+        ctx.begin_path();
+        ctx.move_to(0.0, base_y + 12.0 * drift.sin());
+        for step in 1..=20 {
+            let x = step as f64 * 40.0;
+            ctx.line_to(x, base_y + 12.0 * (x / 80.0 + drift).sin());
+        }
+        ctx.stroke();
+    }
+    ctx.restore();
+}
+
+// Restricts later drawing to a lit circle around `center`, repeated across
+// the screen edges
+pub fn clip_to_light(ctx: &CanvasRenderingContext2d, center: &Vector) {
+    ctx.begin_path();
+    for shift_x in [-WORLD_WIDTH, 0.0, WORLD_WIDTH] {
+        for shift_y in [-WORLD_HEIGHT, 0.0, WORLD_HEIGHT] {
+            let (x, y) = (center.x + shift_x, center.y + shift_y);
+            ctx.move_to(x + BLACKOUT_RADIUS, y);
+            ctx.arc(x, y, BLACKOUT_RADIUS, 0.0, 2.0 * PI).unwrap();
+        }
+    }
+    ctx.clip();
+}
+
+// A cold frame around the screen while time runs slow
+pub fn draw_time_dilation(ctx: &CanvasRenderingContext2d, theme: &Theme) {
+    ctx.save();
+    ctx.set_stroke_style_str(theme.highlight);
+    ctx.set_global_alpha(0.35);
+    ctx.set_line_width(8.0);
+    ctx.stroke_rect(4.0, 4.0, WORLD_WIDTH - 8.0, WORLD_HEIGHT - 8.0);
+    ctx.restore();
+}
+
+// Marks the edge of the lit circle during a blackout
+pub fn draw_light_edge(ctx: &CanvasRenderingContext2d, center: &Vector, theme: &Theme) {
+    ctx.save();
+    ctx.set_stroke_style_str(theme.special);
+    ctx.set_global_alpha(0.3);
+    ctx.set_line_width(theme.line_scale);
+    ctx.begin_path();
+    ctx.arc(center.x, center.y, BLACKOUT_RADIUS, 0.0, 2.0 * PI).unwrap();
+    ctx.stroke();
+    ctx.restore();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGIN: Vector = Vector { x: 400.0, y: 300.0 };
+
+    fn kinds(disruptions: &Disruptions) -> Vec<DisruptionType> {
+        disruptions.active.iter().map(|effect| effect.kind).collect()
+    }
+
+    fn tick_for(disruptions: &mut Disruptions, frames: i32) -> (Vec<DisruptionType>, Vec<DisruptionType>) {
+        let mut changes = (Vec::new(), Vec::new());
+        for _ in 0..frames {
+            let (ended, started) = disruptions.tick();
+            changes.0.extend(ended);
+            changes.1.extend(started);
+        }
+        changes
+    }
+
+    #[test]
+    fn different_kinds_stack_up_to_the_cap_and_the_rest_queue() {
+        use DisruptionType::*;
+        let mut disruptions = Disruptions::new();
+        for kind in [GravityPulse, Disabled, Blackout] {
+            assert!(disruptions.trigger(kind, &ORIGIN) == Trigger::Started);
+        }
+        assert!(disruptions.trigger(MirrorWorld, &ORIGIN) == Trigger::Queued);
+        assert!(disruptions.trigger(MirrorWorld, &ORIGIN) == Trigger::Queued);
+        assert!(disruptions.trigger(Scrambled, &ORIGIN) == Trigger::Queued);
+        assert_eq!(disruptions.active.len(), MAX_ACTIVE_DISRUPTIONS);
+        assert!(!disruptions.has(MirrorWorld));
+
+        // The first to finish makes room for the first in line, queued once
+        let (ended, started) = tick_for(&mut disruptions, GravityPulse.duration());
+        assert!(ended == [GravityPulse]);
+        assert!(started == [MirrorWorld]);
+        assert!(kinds(&disruptions) == [Disabled, Blackout, MirrorWorld]);
+
+        let (ended, started) = tick_for(&mut disruptions, Disabled.duration() - GravityPulse.duration());
+        assert!(ended == [Disabled]);
+        assert!(started == [Scrambled]);
+        assert!(tick_for(&mut disruptions, Scrambled.duration()).1.is_empty());
+    }
+
+    #[test]
+    fn hitting_a_running_kind_restarts_its_timer() {
+        let mut disruptions = Disruptions::new();
+        disruptions.trigger(DisruptionType::TimeDilation, &ORIGIN);
+        tick_for(&mut disruptions, 100);
+        assert_eq!(disruptions.active[0].remaining, DisruptionType::TimeDilation.duration() - 100);
+
+        let pulse_origin = Vector { x: 10.0, y: 20.0 };
+        disruptions.trigger(DisruptionType::GravityPulse, &pulse_origin);
+        assert!(disruptions.trigger(DisruptionType::TimeDilation, &ORIGIN) == Trigger::Refreshed);
+        assert_eq!(disruptions.active.len(), 2);
+        assert_eq!(disruptions.active[0].fraction_left(), 1.0);
+        assert_eq!((disruptions.gravity_origin.x, disruptions.gravity_origin.y), (10.0, 20.0));
+    }
+
+    #[test]
+    fn clear_drops_the_queue_too() {
+        use DisruptionType::*;
+        let mut disruptions = Disruptions::new();
+        for kind in [Disabled, Scrambled, Blackout, MirrorWorld] {
+            disruptions.trigger(kind, &ORIGIN);
+        }
+        assert!(disruptions.clear() == [Disabled, Scrambled, Blackout]);
+        let (ended, started) = tick_for(&mut disruptions, 400);
+        assert!(ended.is_empty() && started.is_empty());
+        assert!(disruptions.active.is_empty());
+    }
+
+    #[test]
+    fn weights_pick_which_disruption_a_special_rock_triggers() {
+        let mut settings = DisruptionSettings::new();
+        for kind in ALL_DISRUPTIONS {
+            assert!(settings.set_weight(kind, 0.0));
+        }
+        assert!(!settings.set_weight(DisruptionType::None, 1.0));
+        assert!(settings.choose() == DisruptionType::None);
+
+        settings.set_weight(DisruptionType::Blackout, 2.0);
+        rng::seed(7);
+        assert!((0..50).all(|_| settings.choose() == DisruptionType::Blackout));
+    }
+}
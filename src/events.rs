@@ -0,0 +1,69 @@
+use crate::disruption::DisruptionType;
+use crate::powerup::PowerUpKind;
+use crate::saucer::SaucerKind;
+use crate::scoring::NEAR_MISS_BONUS;
+use crate::state::GameState;
+use crate::weapon::WeaponKind;
+
+// Things that happened during a frame, drained by the page with `Game::drain_events`
+#[derive(Clone, PartialEq)]
+pub enum GameEvent {
+    StateExited(GameState),
+    StateEntered(GameState),
+    HyperspaceJump,
+    HyperspaceMalfunction,
+    ShipSwallowed,
+    ShieldHit,
+    ShieldDepleted,
+    DisruptionStarted(DisruptionType),
+    DisruptionEnded(DisruptionType),
+    SaucerSpawned(SaucerKind),
+    SaucerDestroyed(SaucerKind),
+    PowerUpCollected(PowerUpKind),
+    PowerUpExpired(PowerUpKind),
+    WeaponOverheated,
+    WeaponSelected(WeaponKind),
+    BossSpawned(&'static str),
+    BossPhase(usize),
+    BossDefeated(&'static str),
+    // Carries the new number of lives
+    ExtraLife(i32),
+    // Carries the new combo multiplier; 1 when a combo is lost
+    Combo(i32),
+    NearMiss,
+    // Carries the new entry's place in the high score table, from 1
+    HighScore(usize),
+}
+
+impl GameEvent {
+    // Events are handed to JavaScript as "kind:detail" strings
+    pub fn describe(&self) -> String {
+        match self {
+            GameEvent::StateExited(state) => format!("state_exit:{}", state.name()),
+            GameEvent::StateEntered(state) => format!("state_enter:{}", state.name()),
+            GameEvent::HyperspaceJump => "hyperspace:jump".to_string(),
+            GameEvent::HyperspaceMalfunction => "hyperspace:malfunction".to_string(),
+            GameEvent::ShipSwallowed => "gravity_well:swallowed".to_string(),
+            GameEvent::ShieldHit => "shield:hit".to_string(),
+            GameEvent::ShieldDepleted => "shield:depleted".to_string(),
+            GameEvent::DisruptionStarted(kind) => format!("disruption_start:{}", kind.name()),
+            GameEvent::DisruptionEnded(kind) => format!("disruption_end:{}", kind.name()),
+            GameEvent::SaucerSpawned(kind) => format!("saucer_spawn:{}", kind.name()),
+            GameEvent::SaucerDestroyed(kind) => format!("saucer_destroyed:{}", kind.name()),
+            GameEvent::PowerUpCollected(kind) => format!("power_up:{}", kind.name()),
+            GameEvent::PowerUpExpired(kind) => format!("power_up_expired:{}", kind.name()),
+            GameEvent::WeaponOverheated => "weapon:overheated".to_string(),
+            GameEvent::WeaponSelected(kind) => format!("weapon_selected:{}", kind.name()),
+            GameEvent::BossSpawned(name) => format!("boss_spawn:{}", name),
+            GameEvent::BossPhase(phase) => format!("boss_phase:{}", phase),
+            GameEvent::BossDefeated(name) => format!("boss_defeated:{}", name),
+            GameEvent::ExtraLife(lives) => format!("extra_life:{}", lives),
+            GameEvent::Combo(multiplier) => format!("combo:{}", multiplier),
+            GameEvent::NearMiss => format!("near_miss:{}", NEAR_MISS_BONUS),
+            GameEvent::HighScore(rank) => format!("high_score:{}", rank),
+        }
+    }
+}
+
+// Oldest events are dropped if the page never drains the queue
+pub const MAX_QUEUED_EVENTS: usize = 256;
@@ -0,0 +1,168 @@
+use std::f64::consts::PI;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::accessibility::Theme;
+use crate::{wrap_delta, Vector};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WellKind {
+    Planet,
+    BlackHole,
+}
+
+// Most a well may pull on the ship just before swallowing it: half the
+// ship's thrust of 0.1 per frame, so full thrust always climbs out
+const MAX_CAPTURE_PULL: f64 = 0.05;
+
+// Radius of the ship's hull, which counts against a planet's surface
+const SHIP_RADIUS: f64 = 10.0;
+
+impl WellKind {
+    // Pull at distance d is strength / d², set from the pull where the ship is
+    // swallowed; a planet pulls a little more gently than a black hole
+    fn strength(&self) -> f64 {
+        let share = match self {
+            WellKind::Planet => 0.9,
+            WellKind::BlackHole => 1.0,
+        };
+        share * MAX_CAPTURE_PULL * self.capture_distance().powi(2)
+    }
+
+    // Distance from the centre at which the ship is lost
+    fn capture_distance(&self) -> f64 {
+        match self {
+            WellKind::Planet => self.horizon() + SHIP_RADIUS,
+            WellKind::BlackHole => self.horizon(),
+        }
+    }
+
+    // Anything closer than this is swallowed: the planet's surface, or the
+    // black hole's event horizon
+    fn horizon(&self) -> f64 {
+        match self {
+            WellKind::Planet => 30.0,
+            WellKind::BlackHole => 40.0,
+        }
+    }
+}
+
+// Asteroids slingshotting around a well are held to this speed
+pub const MAX_ASTEROID_SPEED: f64 = 6.0;
+
+pub struct GravityWell {
+    pub kind: WellKind,
+    pub position: Vector,
+    spin: f64,
+}
+
+impl GravityWell {
+    pub fn new(kind: WellKind, x: f64, y: f64) -> Self {
+        GravityWell { kind, position: Vector { x, y }, spin: 0.0 }
+    }
+
+    pub fn update(&mut self) {
+        self.spin += 0.02;
+    }
+
+    // Inverse-square pull toward the nearest image of the well. Inside the
+    // horizon the pull is held at its surface value.
+    pub fn acceleration(&self, position: &Vector) -> Vector {
+        let delta = wrap_delta(position, &self.position);
+        let distance_squared = (delta.x * delta.x + delta.y * delta.y).max(self.kind.horizon().powi(2));
+        let distance = distance_squared.sqrt();
+        let pull = self.kind.strength() / distance_squared;
+        Vector { x: delta.x / distance * pull, y: delta.y / distance * pull }
+    }
+
+    // True once a body of radius `size` has crossed the horizon; for a black
+    // hole its centre has to cross, for a planet any contact counts
+    pub fn swallows(&self, position: &Vector, size: f64) -> bool {
+        let delta = wrap_delta(position, &self.position);
+        let distance = (delta.x * delta.x + delta.y * delta.y).sqrt();
+        match self.kind {
+            WellKind::Planet => distance < self.kind.horizon() + size,
+            WellKind::BlackHole => distance < self.kind.horizon(),
+        }
+    }
+
+    // Clearance to keep spawning rocks out of the well's grip
+    pub fn keep_clear(&self, position: &Vector, margin: f64) -> bool {
+        let delta = wrap_delta(position, &self.position);
+        (delta.x * delta.x + delta.y * delta.y).sqrt() > self.kind.horizon() + margin
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+        let radius = self.kind.horizon();
+        ctx.save();
+        ctx.translate(self.position.x, self.position.y).unwrap();
+        ctx.set_line_width(theme.line_scale);
+
+        match self.kind {
+            WellKind::Planet => {
+                ctx.set_fill_style_str("black");
+                ctx.set_stroke_style_str("white");
+                ctx.begin_path();
+                ctx.arc(0.0, 0.0, radius, 0.0, 2.0 * PI).unwrap();
+                ctx.fill();
+                ctx.stroke();
+
+                // Tilted ring, with the back half hidden behind the planet
+                ctx.set_stroke_style_str(theme.highlight);
+                ctx.rotate(-0.4).unwrap();
+                ctx.begin_path();
+                ctx.ellipse(0.0, 0.0, radius * 1.8, radius * 0.45, 0.0, 0.0, PI).unwrap();
+                ctx.stroke();
+            }
+            WellKind::BlackHole => {
+                // Accretion disc: broken arcs swirling inward at different speeds
+                ctx.set_stroke_style_str(theme.special);
+                for band in 0..4 {
+                    let band_radius = radius * (1.25 + band as f64 * 0.25);
+                    let turn = self.spin * (4.0 - band as f64);
+                    ctx.set_global_alpha(0.9 - band as f64 * 0.2);
+                    for arc in 0..3 {
+                        let start = turn + arc as f64 * 2.0 * PI / 3.0;
+                        ctx.begin_path();
+                        ctx.arc(0.0, 0.0, band_radius, start, start + 1.2).unwrap();
+                        ctx.stroke();
+                    }
+                }
+
+                ctx.set_global_alpha(1.0);
+                ctx.set_fill_style_str("black");
+                ctx.set_stroke_style_str("white");
+                ctx.begin_path();
+                ctx.arc(0.0, 0.0, radius, 0.0, 2.0 * PI).unwrap();
+                ctx.fill();
+                ctx.stroke();
+            }
+        }
+
+        ctx.restore();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thrust_beats_the_pull_wherever_the_ship_survives() {
+        for kind in [WellKind::Planet, WellKind::BlackHole] {
+            let well = GravityWell::new(kind, 400.0, 300.0);
+            let capture = kind.capture_distance();
+            assert!(well.swallows(&Vector { x: 400.0 + capture - 0.5, y: 300.0 }, SHIP_RADIUS));
+            assert!(!well.swallows(&Vector { x: 400.0 + capture + 0.5, y: 300.0 }, SHIP_RADIUS));
+
+            let pull = well.acceleration(&Vector { x: 400.0 + capture, y: 300.0 });
+            assert!((pull.x * pull.x + pull.y * pull.y).sqrt() <= MAX_CAPTURE_PULL + 1e-9);
+        }
+    }
+
+    #[test]
+    fn pull_reaches_across_the_screen_edge() {
+        let well = GravityWell::new(WellKind::Planet, 10.0, 300.0);
+        let pull = well.acceleration(&Vector { x: 790.0, y: 300.0 });
+        assert!(pull.x > 0.0);
+    }
+}
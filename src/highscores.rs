@@ -0,0 +1,339 @@
+use crate::settings::Difficulty;
+
+pub const MAX_ENTRIES: usize = 10;
+pub const MAX_NAME_LENGTH: usize = 12;
+
+// Mixed into every checksum so a hand-edited entry can't simply be rehashed
+// with a standard FNV-1a tool. This only makes tampering evident; it isn't
+// meant to stop someone who reads the source.
+const CHECKSUM_SALT: &str = "spacerock-high-scores-v1";
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+#[cfg(target_arch = "wasm32")]
+const STORAGE_KEY: &str = "spacerock-high-scores";
+#[cfg(not(target_arch = "wasm32"))]
+const SCORES_FILE_VAR: &str = "SPACEROCK_SCORES";
+
+#[derive(Clone, PartialEq)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: i32,
+    pub level: i32,
+    pub difficulty: Difficulty,
+    // ISO date, such as 2024-05-31
+    pub date: String,
+    pub seed: u32,
+}
+
+impl HighScoreEntry {
+    // Tab-separated "name, score, level, difficulty, date, seed", as stored
+    // (before the checksum) and as handed to pages
+    pub fn fields(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.name,
+            self.score,
+            self.level,
+            self.difficulty.name(),
+            self.date,
+            self.seed
+        )
+    }
+
+    fn checksum(fields: &str) -> u64 {
+        CHECKSUM_SALT.bytes().chain(fields.bytes()).fold(FNV_OFFSET, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+    }
+
+    // One tab-separated line per entry, ending in its checksum
+    fn encode(&self) -> String {
+        let fields = self.fields();
+        format!("{}\t{:016x}", fields, HighScoreEntry::checksum(&fields))
+    }
+
+    // None if the line is malformed or its checksum doesn't match
+    fn decode(line: &str) -> Option<HighScoreEntry> {
+        let (fields, checksum) = line.rsplit_once('\t')?;
+        if u64::from_str_radix(checksum, 16).ok()? != HighScoreEntry::checksum(fields) {
+            return None;
+        }
+        let parts: Vec<&str> = fields.split('\t').collect();
+        if parts.len() != 6 {
+            return None;
+        }
+        Some(HighScoreEntry {
+            name: parts[0].to_string(),
+            score: parts[1].parse().ok()?,
+            level: parts[2].parse().ok()?,
+            difficulty: Difficulty::from_name(parts[3])?,
+            date: parts[4].to_string(),
+            seed: parts[5].parse().ok()?,
+        })
+    }
+}
+
+// Keeps a name to one line of the table: no tabs, newlines or other control
+// characters, and no more than MAX_NAME_LENGTH characters
+pub fn clean_name(name: &str) -> String {
+    name.chars().filter(|c| !c.is_control()).take(MAX_NAME_LENGTH).collect::<String>().trim().to_string()
+}
+
+// Where the table is kept between sessions
+pub trait ScoreStore {
+    fn load(&self) -> Option<String>;
+    fn save(&mut self, data: &str);
+}
+
+// Used when nothing better is available; scores last until the page closes
+pub struct MemoryStore {
+    data: Option<String>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        MemoryStore { data: None }
+    }
+}
+
+impl ScoreStore for MemoryStore {
+    fn load(&self) -> Option<String> {
+        self.data.clone()
+    }
+
+    fn save(&mut self, data: &str) {
+        self.data = Some(data.to_string());
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorageStore {
+    storage: web_sys::Storage,
+    key: String,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorageStore {
+    // None when the browser has storage turned off
+    pub fn open(key: &str) -> Option<Self> {
+        let storage = web_sys::window()?.local_storage().ok()??;
+        Some(LocalStorageStore { storage, key: key.to_string() })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl ScoreStore for LocalStorageStore {
+    fn load(&self) -> Option<String> {
+        self.storage.get_item(&self.key).ok()?
+    }
+
+    // A full or locked storage just means the scores aren't kept
+    fn save(&mut self, data: &str) {
+        let _ = self.storage.set_item(&self.key, data);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStore {
+    path: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStore {
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        FileStore { path: path.into() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ScoreStore for FileStore {
+    fn load(&self) -> Option<String> {
+        std::fs::read_to_string(&self.path).ok()
+    }
+
+    fn save(&mut self, data: &str) {
+        let _ = std::fs::write(&self.path, data);
+    }
+}
+
+// The browser keeps scores in localStorage, falling back to memory when
+// it's unavailable
+#[cfg(target_arch = "wasm32")]
+pub fn default_store() -> Box<dyn ScoreStore> {
+    match LocalStorageStore::open(STORAGE_KEY) {
+        Some(store) => Box::new(store),
+        None => Box::new(MemoryStore::new()),
+    }
+}
+
+// Native builds keep scores in memory unless SPACEROCK_SCORES names a file
+#[cfg(not(target_arch = "wasm32"))]
+pub fn default_store() -> Box<dyn ScoreStore> {
+    match std::env::var(SCORES_FILE_VAR) {
+        Ok(path) => Box::new(FileStore::new(path)),
+        Err(_) => Box::new(MemoryStore::new()),
+    }
+}
+
+pub struct HighScores {
+    entries: Vec<HighScoreEntry>,
+    store: Box<dyn ScoreStore>,
+    // Stored entries dropped on load because their checksum didn't match
+    pub rejected: usize,
+}
+
+impl HighScores {
+    pub fn load(store: Box<dyn ScoreStore>) -> Self {
+        let mut entries = Vec::new();
+        let mut rejected = 0;
+        for line in store.load().unwrap_or_default().lines().filter(|line| !line.is_empty()) {
+            match HighScoreEntry::decode(line) {
+                Some(entry) => entries.push(entry),
+                None => rejected += 1,
+            }
+        }
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        entries.truncate(MAX_ENTRIES);
+        HighScores { entries, store, rejected }
+    }
+
+    pub fn entries(&self) -> &[HighScoreEntry] {
+        &self.entries
+    }
+
+    pub fn qualifies(&self, score: i32) -> bool {
+        score > 0 && (self.entries.len() < MAX_ENTRIES || self.entries.iter().any(|entry| score > entry.score))
+    }
+
+    // Returns the entry's place in the table, counting from 0, if it made it.
+    // Ties go below the scores already there.
+    pub fn insert(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+        let rank = self.entries.iter().position(|existing| entry.score > existing.score).unwrap_or(self.entries.len());
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_ENTRIES);
+        self.save();
+        Some(rank)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.rejected = 0;
+        self.save();
+    }
+
+    fn save(&mut self) {
+        let data: Vec<String> = self.entries.iter().map(HighScoreEntry::encode).collect();
+        self.store.save(&data.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, score: i32) -> HighScoreEntry {
+        HighScoreEntry {
+            name: name.to_string(),
+            score,
+            level: 3,
+            difficulty: Difficulty::Normal,
+            date: "2024-05-31".to_string(),
+            seed: 42,
+        }
+    }
+
+    fn empty_table() -> HighScores {
+        HighScores::load(Box::new(MemoryStore::new()))
+    }
+
+    fn names(scores: &HighScores) -> Vec<&str> {
+        scores.entries().iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    // Reloads the table from whatever it last saved
+    fn reload(scores: &HighScores) -> HighScores {
+        let mut store = MemoryStore::new();
+        store.save(&scores.store.load().unwrap_or_default());
+        HighScores::load(Box::new(store))
+    }
+
+    #[test]
+    fn insert_ranks_best_first_and_ties_go_below() {
+        let mut scores = empty_table();
+        assert_eq!(scores.insert(entry("B", 200)), Some(0));
+        assert_eq!(scores.insert(entry("A", 300)), Some(0));
+        assert_eq!(scores.insert(entry("C", 100)), Some(2));
+        assert_eq!(scores.insert(entry("B2", 200)), Some(2));
+        assert_eq!(names(&scores), ["A", "B", "B2", "C"]);
+    }
+
+    #[test]
+    fn table_is_truncated_to_max_entries() {
+        let mut scores = empty_table();
+        for score in 1..=(MAX_ENTRIES as i32 + 5) {
+            scores.insert(entry("P", score * 10));
+        }
+        assert_eq!(scores.entries().len(), MAX_ENTRIES);
+        assert_eq!(scores.entries()[0].score, (MAX_ENTRIES as i32 + 5) * 10);
+        assert_eq!(scores.entries()[MAX_ENTRIES - 1].score, 60);
+    }
+
+    #[test]
+    fn full_table_only_takes_better_scores() {
+        let mut scores = empty_table();
+        for score in 1..=MAX_ENTRIES as i32 {
+            scores.insert(entry("P", score * 10));
+        }
+        assert!(!scores.qualifies(10));
+        assert!(!scores.qualifies(5));
+        assert!(scores.qualifies(11));
+        assert_eq!(scores.insert(entry("Low", 10)), None);
+        assert_eq!(scores.insert(entry("High", 1000)), Some(0));
+        assert_eq!(scores.entries().len(), MAX_ENTRIES);
+        assert!(!empty_table().qualifies(0));
+    }
+
+    #[test]
+    fn saved_table_loads_back_unchanged() {
+        let mut scores = empty_table();
+        scores.insert(entry("Ada", 1500));
+        scores.insert(HighScoreEntry { difficulty: Difficulty::Hard, seed: u32::MAX, ..entry("Émile", 900) });
+
+        let loaded = reload(&scores);
+        assert_eq!(loaded.rejected, 0);
+        assert!(loaded.entries() == scores.entries());
+    }
+
+    #[test]
+    fn tampered_lines_are_rejected() {
+        let mut scores = empty_table();
+        scores.insert(entry("Ada", 1500));
+        scores.insert(entry("Bob", 700));
+
+        let saved = scores.store.load().unwrap();
+        let mut store = MemoryStore::new();
+        store.save(&saved.replace("\t700\t", "\t99999\t"));
+        let loaded = HighScores::load(Box::new(store));
+        assert_eq!(loaded.rejected, 1);
+        assert_eq!(names(&loaded), ["Ada"]);
+
+        let mut store = MemoryStore::new();
+        store.save("not an entry\nAda\t1\t1\tnormal\t2024-05-31\t0\tzz");
+        let loaded = HighScores::load(Box::new(store));
+        assert_eq!(loaded.rejected, 2);
+        assert!(loaded.entries().is_empty());
+    }
+
+    #[test]
+    fn clean_name_strips_control_characters_and_limits_length() {
+        assert_eq!(clean_name("Ada\tLove\nlace"), "AdaLovelace");
+        assert_eq!(clean_name("  Bob  "), "Bob");
+        assert_eq!(clean_name("ABCDEFGHIJKLMNOP").chars().count(), MAX_NAME_LENGTH);
+        assert_eq!(clean_name("ÉÉÉÉÉÉÉÉÉÉÉÉÉÉ").chars().count(), MAX_NAME_LENGTH);
+        assert_eq!(clean_name("\t\n"), "");
+    }
+}
@@ -0,0 +1,151 @@
+use std::f64::consts::PI;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::rng;
+use crate::{wrap_delta, Asteroid, Vector, WORLD_HEIGHT, WORLD_WIDTH};
+
+// Length of each half of the jump: collapsing out, then expanding back in
+pub const WARP_FRAMES: i32 = 24;
+const LANDING_ATTEMPTS: usize = 24;
+const SAFE_CLEARANCE: f64 = 120.0;
+
+pub struct HyperspaceSettings {
+    pub cooldown_frames: i32,
+    pub malfunction_chance: f64,
+    pub safe_landing: bool,
+}
+
+impl HyperspaceSettings {
+    pub fn new() -> Self {
+        HyperspaceSettings {
+            cooldown_frames: 180, // 3 seconds at 60 FPS
+            malfunction_chance: 0.05,
+            safe_landing: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WarpPhase {
+    Idle,
+    Departing(i32),
+    Arriving(i32),
+}
+
+// What the game has to handle when a warp phase runs out
+pub enum WarpStep {
+    Jump,
+    Landed,
+}
+
+pub struct HyperspaceDrive {
+    pub phase: WarpPhase,
+    pub cooldown: i32,
+}
+
+impl HyperspaceDrive {
+    pub fn new() -> Self {
+        HyperspaceDrive { phase: WarpPhase::Idle, cooldown: 0 }
+    }
+
+    pub fn is_warping(&self) -> bool {
+        self.phase != WarpPhase::Idle
+    }
+
+    pub fn ready(&self) -> bool {
+        self.phase == WarpPhase::Idle && self.cooldown == 0
+    }
+
+    pub fn engage(&mut self, cooldown_frames: i32) {
+        self.phase = WarpPhase::Departing(WARP_FRAMES);
+        self.cooldown = cooldown_frames.max(0);
+    }
+
+    pub fn arrive(&mut self) {
+        self.phase = WarpPhase::Arriving(WARP_FRAMES);
+    }
+
+    pub fn tick(&mut self) -> Option<WarpStep> {
+        match self.phase {
+            WarpPhase::Idle => {
+                self.cooldown = (self.cooldown - 1).max(0);
+                None
+            }
+            WarpPhase::Departing(frames) if frames <= 1 => Some(WarpStep::Jump),
+            WarpPhase::Departing(frames) => {
+                self.phase = WarpPhase::Departing(frames - 1);
+                None
+            }
+            WarpPhase::Arriving(frames) if frames <= 1 => {
+                self.phase = WarpPhase::Idle;
+                Some(WarpStep::Landed)
+            }
+            WarpPhase::Arriving(frames) => {
+                self.phase = WarpPhase::Arriving(frames - 1);
+                None
+            }
+        }
+    }
+
+    // How large the ship is drawn: shrinking away, then growing back
+    pub fn ship_scale(&self) -> f64 {
+        match self.phase {
+            WarpPhase::Idle => 1.0,
+            WarpPhase::Departing(frames) => frames as f64 / WARP_FRAMES as f64,
+            WarpPhase::Arriving(frames) => 1.0 - frames as f64 / WARP_FRAMES as f64,
+        }
+    }
+
+    // Rings that close in on the ship as it leaves and open out as it lands
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, position: &Vector, color: &str) {
+        if !self.is_warping() {
+            return;
+        }
+
+        let scale = self.ship_scale();
+        ctx.save();
+        ctx.set_stroke_style_str(color);
+        ctx.set_global_alpha(1.0 - scale * 0.5);
+        for ring in 1..=3 {
+            let radius = ring as f64 * 12.0 * (0.3 + scale);
+            ctx.begin_path();
+            ctx.arc(position.x, position.y, radius, 0.0, 2.0 * PI).unwrap();
+            ctx.stroke();
+        }
+        ctx.restore();
+    }
+}
+
+// With safe landing, try several random spots and take the first well clear
+// of every rock, or failing that the clearest one found
+pub fn choose_destination(asteroids: &[Asteroid], safe_landing: bool) -> Vector {
+    let random_point = || Vector { x: rng::random() * WORLD_WIDTH, y: rng::random() * WORLD_HEIGHT };
+    if !safe_landing {
+        return random_point();
+    }
+
+    let clearance = |point: &Vector| {
+        asteroids
+            .iter()
+            .map(|asteroid| {
+                let delta = wrap_delta(point, &asteroid.position);
+                (delta.x * delta.x + delta.y * delta.y).sqrt() - asteroid.size
+            })
+            .fold(f64::INFINITY, f64::min)
+    };
+
+    let mut best = random_point();
+    let mut best_clearance = clearance(&best);
+    for _ in 1..LANDING_ATTEMPTS {
+        if best_clearance >= SAFE_CLEARANCE {
+            break;
+        }
+        let candidate = random_point();
+        let candidate_clearance = clearance(&candidate);
+        if candidate_clearance > best_clearance {
+            best = candidate;
+            best_clearance = candidate_clearance;
+        }
+    }
+    best
+}
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+
+// Built-in catalogs. Each file is a Fluent-style list of `key = value` lines
+// with `{ $name }` number placeables; plural variants are written as `key.one`,
+// `key.other` and so on, and `-`-prefixed terms describe number formatting
+// and text direction.
+const BUILTIN_CATALOGS: [(&str, &str); 5] = [
+    ("en", include_str!("locales/en.ftl")),
+    ("es", include_str!("locales/es.ftl")),
+    ("fr", include_str!("locales/fr.ftl")),
+    ("de", include_str!("locales/de.ftl")),
+    ("ar", include_str!("locales/ar.ftl")),
+];
+
+pub const FALLBACK_LOCALE: &str = "en";
+
+#[derive(Clone, Copy, PartialEq)]
+enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    fn suffix(&self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+
+    // CLDR cardinal rules for integers in the shipped languages
+    fn for_count(language: &str, count: i64) -> PluralCategory {
+        let n = count.abs();
+        match language {
+            "fr" => {
+                if n <= 1 {
+                    PluralCategory::One
+                } else {
+                    PluralCategory::Other
+                }
+            }
+            "ar" => match (n, n % 100) {
+                (0, _) => PluralCategory::Zero,
+                (1, _) => PluralCategory::One,
+                (2, _) => PluralCategory::Two,
+                (_, 3..=10) => PluralCategory::Few,
+                (_, 11..=99) => PluralCategory::Many,
+                _ => PluralCategory::Other,
+            },
+            _ => {
+                if n == 1 {
+                    PluralCategory::One
+                } else {
+                    PluralCategory::Other
+                }
+            }
+        }
+    }
+}
+
+fn parse_catalog(source: &str) -> HashMap<String, String> {
+    let mut messages = HashMap::new();
+    for line in source.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim();
+            // Quotes keep whitespace-only values such as digit separators
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+            messages.insert(key.trim().to_string(), value.to_string());
+        }
+    }
+    messages
+}
+
+// "fr-CA" and "fr_ca" both normalise to "fr-ca"
+fn normalize(locale: &str) -> String {
+    locale.trim().replace('_', "-").to_lowercase()
+}
+
+fn language_of(locale: &str) -> &str {
+    locale.split('-').next().unwrap_or(locale)
+}
+
+pub struct Localizer {
+    catalogs: HashMap<String, HashMap<String, String>>,
+    // Most specific locale first, always ending in the fallback
+    chain: Vec<String>,
+}
+
+impl Localizer {
+    pub fn new() -> Self {
+        let mut localizer = Localizer { catalogs: HashMap::new(), chain: vec![FALLBACK_LOCALE.to_string()] };
+        for (locale, source) in BUILTIN_CATALOGS {
+            localizer.load(locale, source);
+        }
+        localizer
+    }
+
+    // Adds or extends a catalog; keys already present are overwritten
+    pub fn load(&mut self, locale: &str, source: &str) {
+        let catalog = self.catalogs.entry(normalize(locale)).or_default();
+        catalog.extend(parse_catalog(source));
+    }
+
+    // Returns false when neither the locale nor its language has a catalog,
+    // in which case everything falls back to English
+    pub fn set_locale(&mut self, locale: &str) -> bool {
+        let requested = normalize(locale);
+        let mut chain = Vec::new();
+        for candidate in [requested.as_str(), language_of(&requested)] {
+            if self.catalogs.contains_key(candidate) && !chain.iter().any(|locale| locale == candidate) {
+                chain.push(candidate.to_string());
+            }
+        }
+        let found = !chain.is_empty();
+        if !chain.iter().any(|locale| locale == FALLBACK_LOCALE) {
+            chain.push(FALLBACK_LOCALE.to_string());
+        }
+        self.chain = chain;
+        found
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.chain[0]
+    }
+
+    pub fn available(&self) -> Vec<String> {
+        let mut locales: Vec<String> = self.catalogs.keys().cloned().collect();
+        locales.sort();
+        locales
+    }
+
+    pub fn is_rtl(&self) -> bool {
+        self.lookup("-direction") == Some("rtl")
+    }
+
+    fn lookup(&self, key: &str) -> Option<&str> {
+        self.chain
+            .iter()
+            .filter_map(|locale| self.catalogs.get(locale))
+            .find_map(|catalog| catalog.get(key))
+            .map(|value| value.as_str())
+    }
+
+    pub fn text(&self, key: &str) -> String {
+        self.format(key, &[])
+    }
+
+    // Missing keys render as the key itself so gaps are visible in testing
+    pub fn format(&self, key: &str, args: &[(&str, i64)]) -> String {
+        match self.lookup(key) {
+            Some(pattern) => self.substitute(pattern, args),
+            None => key.to_string(),
+        }
+    }
+
+    // Picks the `key.<category>` variant for `count`, falling back to
+    // `key.other` and then the bare key; `count` is available as `$count`
+    pub fn plural(&self, key: &str, count: i64, args: &[(&str, i64)]) -> String {
+        let category = PluralCategory::for_count(language_of(self.locale()), count);
+        let pattern = self
+            .lookup(&format!("{}.{}", key, category.suffix()))
+            .or_else(|| self.lookup(&format!("{}.other", key)))
+            .or_else(|| self.lookup(key));
+        match pattern {
+            Some(pattern) => {
+                let mut all_args = vec![("count", count)];
+                all_args.extend(args.iter().copied());
+                self.substitute(pattern, &all_args)
+            }
+            None => key.to_string(),
+        }
+    }
+
+    // Groups thousands and swaps in the locale's digits
+    pub fn number(&self, value: i64) -> String {
+        let separator = self.lookup("-group-separator").unwrap_or(",");
+        let min_grouping = self.lookup("-min-grouping").and_then(|value| value.parse().ok()).unwrap_or(4);
+        let digits: Vec<char> = self.lookup("-digits").unwrap_or("0123456789").chars().collect();
+
+        let raw = value.unsigned_abs().to_string();
+        let mut grouped = String::new();
+        for (index, digit) in raw.chars().enumerate() {
+            if raw.len() >= min_grouping && index > 0 && (raw.len() - index).is_multiple_of(3) {
+                grouped.push_str(separator);
+            }
+            let digit = digit.to_digit(10).unwrap_or(0) as usize;
+            grouped.push(digits.get(digit).copied().unwrap_or('0'));
+        }
+        if value < 0 {
+            format!("-{}", grouped)
+        } else {
+            grouped
+        }
+    }
+
+    fn substitute(&self, pattern: &str, args: &[(&str, i64)]) -> String {
+        let mut result = String::new();
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('}') else {
+                result.push_str(&rest[start..]);
+                return result;
+            };
+            let name = rest[start + 1..start + end].trim().trim_start_matches('$');
+            match args.iter().find(|(arg_name, _)| *arg_name == name) {
+                Some((_, value)) => result.push_str(&self.number(*value)),
+                None => result.push_str(&rest[start..start + end + 1]),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn localizer(locale: &str) -> Localizer {
+        let mut localizer = Localizer::new();
+        assert!(localizer.set_locale(locale));
+        localizer
+    }
+
+    #[test]
+    fn plural_categories_follow_each_language() {
+        let english = localizer("en");
+        assert_eq!(english.plural("hud-lives", 1, &[]), "Lives: 1");
+        assert_eq!(english.plural("hud-lives", 0, &[]), "Lives: 0");
+
+        let french = localizer("fr");
+        assert_eq!(french.plural("hud-lives", 0, &[]), "Vie : 0");
+        assert_eq!(french.plural("hud-lives", 2, &[]), "Vies : 2");
+
+        let arabic = localizer("ar");
+        assert_eq!(arabic.plural("hud-lives", 0, &[]), "لا أرواح متبقية");
+        assert_eq!(arabic.plural("hud-lives", 2, &[]), "روحان");
+        assert_eq!(arabic.plural("hud-lives", 5, &[]), "٥ أرواح");
+        assert_eq!(arabic.plural("hud-lives", 11, &[]), "١١ روحًا");
+        assert_eq!(arabic.plural("hud-lives", 100, &[]), "١٠٠ روح");
+        assert_eq!(arabic.plural("hud-lives", 103, &[]), "١٠٣ أرواح");
+    }
+
+    #[test]
+    fn missing_plural_variants_fall_back() {
+        let mut localizer = localizer("en");
+        localizer.load("en", "rocks.other = { $count } rocks left\nwaves = { $count } waves");
+        assert_eq!(localizer.plural("rocks", 1, &[]), "1 rocks left");
+        assert_eq!(localizer.plural("waves", 3, &[]), "3 waves");
+        assert_eq!(localizer.plural("nothing", 3, &[]), "nothing");
+    }
+
+    #[test]
+    fn numbers_are_grouped_the_locale_way() {
+        let english = localizer("en");
+        assert_eq!(english.number(999), "999");
+        assert_eq!(english.number(1234), "1,234");
+        assert_eq!(english.number(1234567), "1,234,567");
+        assert_eq!(english.number(-12345), "-12,345");
+
+        // Spanish leaves four-digit numbers ungrouped
+        let spanish = localizer("es");
+        assert_eq!(spanish.number(1234), "1234");
+        assert_eq!(spanish.number(12345), "12.345");
+
+        assert_eq!(localizer("fr").number(12345), "12\u{202f}345");
+        assert_eq!(localizer("ar").number(12345), "١٢٬٣٤٥");
+    }
+
+    #[test]
+    fn placeables_use_the_number_format() {
+        let german = localizer("de-AT");
+        assert_eq!(german.locale(), "de");
+        assert_eq!(german.format("hud-score", &[("score", 25000)]), "Punkte: 25.000");
+
+        let mut localizer = localizer("en");
+        localizer.load("en", "broken = Score { $score");
+        assert_eq!(localizer.format("broken", &[("score", 5)]), "Score { $score");
+        assert_eq!(localizer.text("no-such-key"), "no-such-key");
+    }
+
+    #[test]
+    fn unknown_locales_fall_back_to_english() {
+        let mut localizer = Localizer::new();
+        assert!(!localizer.set_locale("xx-YY"));
+        assert_eq!(localizer.locale(), FALLBACK_LOCALE);
+        assert!(localizer.set_locale("FR_ca"));
+        assert_eq!(localizer.locale(), "fr");
+        assert!(!localizer.is_rtl());
+        assert!(localizer.set_locale("ar"));
+        assert!(localizer.is_rtl());
+    }
+}
@@ -0,0 +1,503 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use wasm_bindgen::JsCast;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::i18n::Localizer;
+use crate::Vector;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ControlScheme {
+    Keyboard,
+    VirtualStick,
+    TapToAim,
+    MouseAim,
+}
+
+impl ControlScheme {
+    pub fn from_name(name: &str) -> Option<ControlScheme> {
+        match name {
+            "keyboard" => Some(ControlScheme::Keyboard),
+            "stick" => Some(ControlScheme::VirtualStick),
+            "tap" => Some(ControlScheme::TapToAim),
+            "mouse" => Some(ControlScheme::MouseAim),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ControlScheme::Keyboard => "keyboard",
+            ControlScheme::VirtualStick => "stick",
+            ControlScheme::TapToAim => "tap",
+            ControlScheme::MouseAim => "mouse",
+        }
+    }
+
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            ControlScheme::Keyboard => "controls-keyboard",
+            ControlScheme::VirtualStick => "controls-stick",
+            ControlScheme::TapToAim => "controls-tap",
+            ControlScheme::MouseAim => "controls-mouse",
+        }
+    }
+
+    pub fn uses_touch(&self) -> bool {
+        matches!(self, ControlScheme::VirtualStick | ControlScheme::TapToAim)
+    }
+
+    pub fn cycled(&self, step: i32) -> ControlScheme {
+        const ALL: [ControlScheme; 4] = [
+            ControlScheme::Keyboard,
+            ControlScheme::MouseAim,
+            ControlScheme::VirtualStick,
+            ControlScheme::TapToAim,
+        ];
+        crate::settings::cycle(&ALL, *self, step)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum TouchRole {
+    Stick,
+    Fire,
+    Thrust,
+    Aim,
+}
+
+struct TouchPointer {
+    role: TouchRole,
+    position: Vector,
+    // Counts up with each press, since browsers can reuse or reorder pointer ids
+    placed: u32,
+}
+
+pub struct TouchLayout {
+    pub stick_center: Vector,
+    pub stick_radius: f64,
+    pub fire_center: Vector,
+    pub thrust_center: Vector,
+    pub button_radius: f64,
+    pub opacity: f64,
+}
+
+impl TouchLayout {
+    fn new() -> Self {
+        TouchLayout {
+            stick_center: Vector { x: 110.0, y: 490.0 },
+            stick_radius: 60.0,
+            fire_center: Vector { x: 710.0, y: 500.0 },
+            thrust_center: Vector { x: 600.0, y: 530.0 },
+            button_radius: 40.0,
+            opacity: 0.4,
+        }
+    }
+}
+
+// What the touch layer asks the ship to do this frame
+pub struct TouchInput {
+    pub rotate: f64,
+    pub thrust: bool,
+    pub fire: bool,
+    pub aim: Option<Vector>,
+}
+
+pub struct TouchControls {
+    pub layout: TouchLayout,
+    pointers: HashMap<i32, TouchPointer>,
+    presses: u32,
+    fire_repeat_timer: i32,
+}
+
+const STICK_DEAD_ZONE: f64 = 0.15;
+const STICK_THRUST_THRESHOLD: f64 = 0.5;
+pub const FIRE_REPEAT_FRAMES: i32 = 12;
+
+// Mouse buttons as reported by MouseEvent.button
+const MOUSE_LEFT: i16 = 0;
+const MOUSE_RIGHT: i16 = 2;
+
+impl TouchControls {
+    pub fn new() -> Self {
+        TouchControls {
+            layout: TouchLayout::new(),
+            pointers: HashMap::new(),
+            presses: 0,
+            fire_repeat_timer: 0,
+        }
+    }
+
+    pub fn pointer_down(&mut self, id: i32, x: f64, y: f64, scheme: ControlScheme) {
+        let position = Vector { x, y };
+        let role = if distance(&position, &self.layout.fire_center) < self.layout.button_radius {
+            TouchRole::Fire
+        } else if scheme == ControlScheme::VirtualStick {
+            if distance(&position, &self.layout.thrust_center) < self.layout.button_radius {
+                TouchRole::Thrust
+            } else if distance(&position, &self.layout.stick_center) < self.layout.stick_radius * 1.5 {
+                TouchRole::Stick
+            } else {
+                return;
+            }
+        } else {
+            TouchRole::Aim
+        };
+
+        if role == TouchRole::Fire && !self.is_held(TouchRole::Fire) {
+            self.fire_repeat_timer = 0; // Fire immediately on a fresh press
+        }
+
+        self.presses = self.presses.wrapping_add(1);
+        self.pointers.insert(id, TouchPointer { role, position, placed: self.presses });
+    }
+
+    pub fn pointer_move(&mut self, id: i32, x: f64, y: f64) {
+        if let Some(pointer) = self.pointers.get_mut(&id) {
+            pointer.position = Vector { x, y };
+        }
+    }
+
+    pub fn pointer_up(&mut self, id: i32) {
+        self.pointers.remove(&id);
+    }
+
+    pub fn clear(&mut self) {
+        self.pointers.clear();
+        self.fire_repeat_timer = 0;
+    }
+
+    fn is_held(&self, role: TouchRole) -> bool {
+        self.pointers.values().any(|pointer| pointer.role == role)
+    }
+
+    // Stick deflection normalized to the unit circle
+    fn stick_deflection(&self) -> Option<Vector> {
+        let pointer = self.pointers.values().find(|pointer| pointer.role == TouchRole::Stick)?;
+        let dx = pointer.position.x - self.layout.stick_center.x;
+        let dy = pointer.position.y - self.layout.stick_center.y;
+        let length = (dx * dx + dy * dy).sqrt();
+        let scale = if length > self.layout.stick_radius { 1.0 / length } else { 1.0 / self.layout.stick_radius };
+        Some(Vector { x: dx * scale, y: dy * scale })
+    }
+
+    pub fn sample(&mut self, repeat_frames: i32) -> TouchInput {
+        let mut input = TouchInput {
+            rotate: 0.0,
+            thrust: self.is_held(TouchRole::Thrust),
+            fire: false,
+            aim: None,
+        };
+
+        if let Some(deflection) = self.stick_deflection() {
+            if deflection.x.abs() > STICK_DEAD_ZONE {
+                input.rotate = deflection.x * 0.1;
+            }
+            if deflection.y < -STICK_THRUST_THRESHOLD {
+                input.thrust = true;
+            }
+        }
+
+        // Aim at the most recently placed finger
+        input.aim = self
+            .pointers
+            .values()
+            .filter(|pointer| pointer.role == TouchRole::Aim)
+            .max_by_key(|pointer| pointer.placed)
+            .map(|pointer| pointer.position.clone());
+
+        // Holding fire repeats at a steady rate instead of every frame
+        if self.is_held(TouchRole::Fire) {
+            if self.fire_repeat_timer == 0 {
+                input.fire = true;
+                self.fire_repeat_timer = repeat_frames;
+            }
+            self.fire_repeat_timer -= 1;
+        } else {
+            self.fire_repeat_timer = 0;
+        }
+
+        input
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, scheme: ControlScheme, i18n: &Localizer) {
+        ctx.save();
+        ctx.set_global_alpha(self.layout.opacity);
+        ctx.set_stroke_style_str("white");
+        ctx.set_fill_style_str("white");
+        ctx.set_line_width(2.0);
+        ctx.set_font("14px Arial");
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+
+        if scheme == ControlScheme::VirtualStick {
+            // Stick base and knob
+            let center = &self.layout.stick_center;
+            ctx.begin_path();
+            ctx.arc(center.x, center.y, self.layout.stick_radius, 0.0, 2.0 * PI).unwrap();
+            ctx.stroke();
+
+            let knob = match self.stick_deflection() {
+                Some(deflection) => Vector {
+                    x: center.x + deflection.x * self.layout.stick_radius,
+                    y: center.y + deflection.y * self.layout.stick_radius,
+                },
+                None => center.clone(),
+            };
+            ctx.begin_path();
+            ctx.arc(knob.x, knob.y, self.layout.stick_radius * 0.4, 0.0, 2.0 * PI).unwrap();
+            ctx.fill();
+
+            self.draw_button(ctx, &self.layout.thrust_center, &i18n.text("touch-thrust"), self.is_held(TouchRole::Thrust));
+        }
+
+        self.draw_button(ctx, &self.layout.fire_center, &i18n.text("touch-fire"), self.is_held(TouchRole::Fire));
+
+        // Mark where tap-to-aim fingers are pointing
+        for pointer in self.pointers.values().filter(|pointer| pointer.role == TouchRole::Aim) {
+            ctx.begin_path();
+            ctx.arc(pointer.position.x, pointer.position.y, 15.0, 0.0, 2.0 * PI).unwrap();
+            ctx.stroke();
+        }
+
+        ctx.restore();
+    }
+
+    fn draw_button(&self, ctx: &CanvasRenderingContext2d, center: &Vector, label: &str, pressed: bool) {
+        ctx.begin_path();
+        ctx.arc(center.x, center.y, self.layout.button_radius, 0.0, 2.0 * PI).unwrap();
+        if pressed {
+            ctx.fill();
+            ctx.set_fill_style_str("black");
+            ctx.fill_text(label, center.x, center.y).unwrap();
+            ctx.set_fill_style_str("white");
+        } else {
+            ctx.stroke();
+            ctx.fill_text(label, center.x, center.y).unwrap();
+        }
+    }
+}
+
+fn distance(a: &Vector, b: &Vector) -> f64 {
+    let dx = a.x - b.x;
+    let dy = a.y - b.y;
+    (dx * dx + dy * dy).sqrt()
+}
+
+pub struct MouseControls {
+    pub target: Option<Vector>,
+    pub turn_rate: f64,
+    firing: bool,
+    thrusting: bool,
+    fire_repeat_timer: i32,
+}
+
+impl MouseControls {
+    pub fn new() -> Self {
+        MouseControls {
+            target: None,
+            turn_rate: 0.1,
+            firing: false,
+            thrusting: false,
+            fire_repeat_timer: 0,
+        }
+    }
+
+    pub fn button_down(&mut self, button: i16) {
+        match button {
+            MOUSE_LEFT => {
+                if !self.firing {
+                    self.fire_repeat_timer = 0;
+                }
+                self.firing = true;
+            }
+            MOUSE_RIGHT => self.thrusting = true,
+            _ => {}
+        }
+    }
+
+    pub fn button_up(&mut self, button: i16) {
+        match button {
+            MOUSE_LEFT => self.firing = false,
+            MOUSE_RIGHT => self.thrusting = false,
+            _ => {}
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.target = None;
+        self.firing = false;
+        self.thrusting = false;
+        self.fire_repeat_timer = 0;
+    }
+
+    // Returns (fire, thrust) for this frame
+    pub fn sample(&mut self, repeat_frames: i32) -> (bool, bool) {
+        let mut fire = false;
+        if self.firing {
+            if self.fire_repeat_timer == 0 {
+                fire = true;
+                self.fire_repeat_timer = repeat_frames;
+            }
+            self.fire_repeat_timer -= 1;
+        } else {
+            self.fire_repeat_timer = 0;
+        }
+        (fire, self.thrusting)
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d) {
+        let target = match &self.target {
+            Some(target) => target,
+            None => return,
+        };
+
+        // Crosshair at the cursor
+        ctx.save();
+        ctx.set_global_alpha(0.6);
+        ctx.set_stroke_style_str("white");
+        ctx.set_line_width(1.0);
+        ctx.begin_path();
+        ctx.arc(target.x, target.y, 8.0, 0.0, 2.0 * PI).unwrap();
+        ctx.move_to(target.x - 12.0, target.y);
+        ctx.line_to(target.x + 12.0, target.y);
+        ctx.move_to(target.x, target.y - 12.0);
+        ctx.line_to(target.x, target.y + 12.0);
+        ctx.stroke();
+        ctx.restore();
+    }
+}
+
+// Standard gamepad mapping button indices
+pub const PAD_A: usize = 0;
+pub const PAD_B: usize = 1;
+pub const PAD_Y: usize = 3;
+pub const PAD_LEFT_BUMPER: usize = 4;
+pub const PAD_RIGHT_BUMPER: usize = 5;
+pub const PAD_RIGHT_TRIGGER: usize = 7;
+pub const PAD_START: usize = 9;
+pub const PAD_UP: usize = 12;
+pub const PAD_DOWN: usize = 13;
+pub const PAD_LEFT: usize = 14;
+pub const PAD_RIGHT: usize = 15;
+const PAD_BUTTON_COUNT: usize = 17;
+const PAD_STICK_DEAD_ZONE: f64 = 0.25;
+
+// One frame of gamepad state, merged across every connected pad
+pub struct GamepadFrame {
+    held: [bool; PAD_BUTTON_COUNT],
+    pressed: [bool; PAD_BUTTON_COUNT],
+    pub stick: Vector,
+}
+
+impl GamepadFrame {
+    pub fn held(&self, button: usize) -> bool {
+        self.held[button]
+    }
+
+    pub fn pressed(&self, button: usize) -> bool {
+        self.pressed[button]
+    }
+}
+
+pub struct GamepadControls {
+    previous: [bool; PAD_BUTTON_COUNT],
+    fire_repeat_timer: i32,
+}
+
+impl GamepadControls {
+    pub fn new() -> Self {
+        GamepadControls {
+            previous: [false; PAD_BUTTON_COUNT],
+            fire_repeat_timer: 0,
+        }
+    }
+
+    pub fn poll(&mut self) -> Option<GamepadFrame> {
+        let pads = web_sys::window()?.navigator().get_gamepads().ok()?;
+
+        let mut held = [false; PAD_BUTTON_COUNT];
+        let mut stick = Vector { x: 0.0, y: 0.0 };
+        let mut connected = false;
+        for pad in pads.iter() {
+            let pad = match pad.dyn_into::<web_sys::Gamepad>() {
+                Ok(pad) if pad.connected() => pad,
+                _ => continue,
+            };
+            connected = true;
+
+            for (index, button) in pad.buttons().iter().enumerate().take(PAD_BUTTON_COUNT) {
+                if let Ok(button) = button.dyn_into::<web_sys::GamepadButton>() {
+                    held[index] |= button.pressed();
+                }
+            }
+
+            let axes = pad.axes();
+            let x = axes.get(0).as_f64().unwrap_or(0.0);
+            let y = axes.get(1).as_f64().unwrap_or(0.0);
+            if x.abs() > PAD_STICK_DEAD_ZONE { stick.x = x; }
+            if y.abs() > PAD_STICK_DEAD_ZONE { stick.y = y; }
+        }
+
+        // Treat the left stick like the d-pad so menus can be driven with either
+        held[PAD_LEFT] |= stick.x < -0.6;
+        held[PAD_RIGHT] |= stick.x > 0.6;
+        held[PAD_UP] |= stick.y < -0.6;
+        held[PAD_DOWN] |= stick.y > 0.6;
+
+        let mut pressed = [false; PAD_BUTTON_COUNT];
+        for index in 0..PAD_BUTTON_COUNT {
+            pressed[index] = held[index] && !self.previous[index];
+        }
+        self.previous = held;
+
+        if connected {
+            Some(GamepadFrame { held, pressed, stick })
+        } else {
+            None
+        }
+    }
+
+    // Fire on press, then repeat while A stays down
+    pub fn fire(&mut self, frame: &GamepadFrame, repeat_frames: i32) -> bool {
+        if !frame.held(PAD_A) {
+            self.fire_repeat_timer = 0;
+            return false;
+        }
+
+        let fire = self.fire_repeat_timer == 0;
+        if fire {
+            self.fire_repeat_timer = repeat_frames;
+        }
+        self.fire_repeat_timer -= 1;
+        fire
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aim(touch: &mut TouchControls) -> Option<(f64, f64)> {
+        touch.sample(FIRE_REPEAT_FRAMES).aim.map(|aim| (aim.x, aim.y))
+    }
+
+    #[test]
+    fn tap_to_aim_follows_the_latest_finger_whatever_its_id() {
+        let mut touch = TouchControls::new();
+        touch.pointer_down(7, 100.0, 100.0, ControlScheme::TapToAim);
+        touch.pointer_down(3, 300.0, 200.0, ControlScheme::TapToAim);
+        assert_eq!(aim(&mut touch), Some((300.0, 200.0)));
+
+        touch.pointer_up(3);
+        assert_eq!(aim(&mut touch), Some((100.0, 100.0)));
+
+        // A reused id counts as a new press
+        touch.pointer_down(3, 500.0, 400.0, ControlScheme::TapToAim);
+        touch.pointer_move(7, 150.0, 120.0);
+        assert_eq!(aim(&mut touch), Some((500.0, 400.0)));
+
+        touch.clear();
+        assert_eq!(aim(&mut touch), None);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::boss::{BossDefinition, BOSSES};
+use crate::gravity::{GravityWell, WellKind};
+use crate::material::{Material, ALL_MATERIALS};
+use crate::rng;
+
+// Every fifth level is a boss fight
+const BOSS_LEVEL_INTERVAL: i32 = 5;
+
+// Layout of a level. Gravity wells appear from level 3 and cycle through
+// these arrangements; the ship respawns at the centre, so wells keep away
+// from it. Boss levels have no wells and only a couple of rocks.
+pub struct LevelDefinition {
+    pub asteroid_count: i32,
+    pub wells: Vec<GravityWell>,
+    pub boss: Option<&'static BossDefinition>,
+    // Spawn weight for each entry of ALL_MATERIALS
+    material_weights: [f64; 6],
+}
+
+const WELL_LAYOUTS: [&[(WellKind, f64, f64)]; 4] = [
+    &[(WellKind::Planet, 160.0, 140.0)],
+    &[(WellKind::BlackHole, 620.0, 450.0)],
+    &[(WellKind::Planet, 640.0, 150.0), (WellKind::BlackHole, 170.0, 440.0)],
+    &[(WellKind::Planet, 150.0, 300.0), (WellKind::Planet, 650.0, 300.0)],
+];
+
+// Level each material first turns up on
+fn first_level(material: Material) -> i32 {
+    match material {
+        Material::Rock => 1,
+        Material::Ice => 2,
+        Material::Metallic => 3,
+        Material::Explosive => 4,
+        Material::Crystal => 5,
+        Material::Dense => 6,
+    }
+}
+
+// A material grows more common for a few levels after it first appears;
+// plain rock stays the most common
+fn material_weight(material: Material, level: i32) -> f64 {
+    match material {
+        Material::Rock => 10.0,
+        _ if level < first_level(material) => 0.0,
+        _ => ((level - first_level(material) + 1) as f64).min(4.0),
+    }
+}
+
+impl LevelDefinition {
+    pub fn for_level(level: i32) -> Self {
+        if level % BOSS_LEVEL_INTERVAL == 0 {
+            let boss = BOSSES[((level / BOSS_LEVEL_INTERVAL - 1) as usize) % BOSSES.len()];
+            return LevelDefinition {
+                asteroid_count: 2,
+                wells: Vec::new(),
+                boss: Some(boss),
+                material_weights: ALL_MATERIALS.map(|material| material_weight(material, level)),
+            };
+        }
+
+        let wells = if level < 3 {
+            Vec::new()
+        } else {
+            WELL_LAYOUTS[((level - 3) as usize) % WELL_LAYOUTS.len()]
+                .iter()
+                .map(|&(kind, x, y)| GravityWell::new(kind, x, y))
+                .collect()
+        };
+
+        LevelDefinition {
+            asteroid_count: 4 + (level - 1) / 4, // Add extra asteroid every 4 levels
+            wells,
+            boss: None,
+            material_weights: ALL_MATERIALS.map(|material| material_weight(material, level)),
+        }
+    }
+
+    pub fn choose_material(&self) -> Material {
+        let total: f64 = self.material_weights.iter().sum();
+        let mut roll = rng::random() * total;
+        for (material, weight) in ALL_MATERIALS.iter().zip(self.material_weights) {
+            roll -= weight;
+            if roll < 0.0 {
+                return *material;
+            }
+        }
+        Material::Rock
+    }
+}
@@ -1,7 +1,73 @@
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::closure::Closure;
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 use std::f64::consts::PI;
-use js_sys::Math;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use js_sys::{Math, Reflect};
+
+mod accessibility;
+mod boss;
+mod collision;
+mod disruption;
+mod events;
+mod gravity;
+mod highscores;
+mod hyperspace;
+mod i18n;
+mod input;
+mod levels;
+mod material;
+mod menu;
+mod particles;
+mod powerup;
+mod rng;
+mod saucer;
+mod scoring;
+mod settings;
+mod shield;
+mod shockwave;
+mod state;
+mod weapon;
+
+use accessibility::{Palette, Theme, HUD_SCALE_MAX, HUD_SCALE_MIN};
+use boss::{Boss, BossAttack};
+use collision::resolve_asteroid_collisions;
+use disruption::{
+    clip_to_light, draw_gravity_pulse, draw_light_edge, draw_magnetic_field, draw_time_dilation, gravity_pull,
+    DisruptionType, Disruptions, Trigger, MAGNETIC_TURN, TIME_DILATION_SCALE,
+};
+use events::{GameEvent, MAX_QUEUED_EVENTS};
+use gravity::{GravityWell, MAX_ASTEROID_SPEED};
+use highscores::{clean_name, default_store, HighScoreEntry, HighScores, MAX_NAME_LENGTH};
+use hyperspace::{choose_destination, HyperspaceDrive, WarpStep};
+use i18n::Localizer;
+use levels::LevelDefinition;
+use material::Material;
+use input::{
+    ControlScheme, GamepadControls, GamepadFrame, MouseControls, TouchControls, PAD_A, PAD_B, PAD_DOWN, PAD_LEFT,
+    FIRE_REPEAT_FRAMES, PAD_LEFT_BUMPER, PAD_RIGHT, PAD_RIGHT_BUMPER, PAD_RIGHT_TRIGGER, PAD_START, PAD_UP, PAD_Y,
+};
+use menu::{Menu, MenuAction, MenuInput, MenuPage, SettingKey};
+use particles::{ParticleQuality, ParticleSystem};
+use powerup::{
+    PowerUp, PowerUpEffects, PowerUpKind, PICKUP_RADIUS, RAPID_FIRE_REPEAT_FRAMES, SPREAD_ANGLE,
+};
+use saucer::{Saucer, SaucerKind, ENEMY_BULLET_SPEED};
+use scoring::{Combo, NearMisses, ScorePopup, NEAR_MISS_BONUS, NEAR_MISS_MARGIN};
+use settings::{cycle, Difficulty, Settings};
+use shield::{Shield, ShieldChange, MAX_ENERGY, SHIELD_RADIUS, SHIELD_RESTITUTION};
+use shockwave::{ShockWave, CHAIN_BONUS};
+use state::GameState;
+use weapon::{
+    draw_beam, ray_cast, Blast, BulletSettings, Mine, Missile, WeaponKind, WeaponState, LASER_HIT_INTERVAL, LASER_RANGE, MAX_HEAT,
+    MAX_MINES, MINE_BLAST_RADIUS, SHOTGUN_PELLETS, SHOTGUN_SPREAD,
+};
+
+const WORLD_WIDTH: f64 = 800.0;
+const WORLD_HEIGHT: f64 = 600.0;
 
 #[derive(Clone)]
 struct Vector {
@@ -9,26 +75,45 @@ struct Vector {
     y: f64,
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum DisruptionType {
-    None,
-    Disabled,
-    Scrambled,
-    Uncontrollable,
+// Offset from `from` to the nearest wrapped image of `to`
+fn wrap_delta(from: &Vector, to: &Vector) -> Vector {
+    let mut dx = to.x - from.x;
+    let mut dy = to.y - from.y;
+    if dx > WORLD_WIDTH / 2.0 { dx -= WORLD_WIDTH; }
+    if dx < -WORLD_WIDTH / 2.0 { dx += WORLD_WIDTH; }
+    if dy > WORLD_HEIGHT / 2.0 { dy -= WORLD_HEIGHT; }
+    if dy < -WORLD_HEIGHT / 2.0 { dy += WORLD_HEIGHT; }
+    Vector { x: dx, y: dy }
+}
+
+fn wrap_distance(from: &Vector, to: &Vector) -> f64 {
+    let delta = wrap_delta(from, to);
+    (delta.x * delta.x + delta.y * delta.y).sqrt()
 }
 
 struct Ship {
     position: Vector,
     velocity: Vector,
     rotation: f64,
+    hyperspace: HyperspaceDrive,
+    shield: Shield,
+    power_ups: PowerUpEffects,
+    weapon: WeaponState,
 }
 
+// Hull radius squared, on the same scale as asteroid mass
+const SHIP_MASS: f64 = 100.0;
+
 impl Ship {
     fn new(x: f64, y: f64) -> Ship {
         Ship {
             position: Vector { x, y },
             velocity: Vector { x: 0.0, y: 0.0 },
             rotation: 0.0,
+            hyperspace: HyperspaceDrive::new(),
+            shield: Shield::new(),
+            power_ups: PowerUpEffects::new(),
+            weapon: WeaponState::new(),
         }
     }
 
@@ -51,6 +136,8 @@ impl Ship {
         ctx.save();
         ctx.translate(self.position.x, self.position.y).unwrap();
         ctx.rotate(self.rotation).unwrap();
+        let scale = self.hyperspace.ship_scale();
+        ctx.scale(scale, scale).unwrap();
         
         ctx.begin_path();
         ctx.move_to(0.0, -20.0);
@@ -67,37 +154,120 @@ struct Bullet {
     position: Vector,
     velocity: Vector,
     lifetime: i32,
+    // Distance left before the bullet fizzles out, and distance covered so far
+    range: f64,
+    travelled: f64,
+    wraps: bool,
+    piercing: bool,
+    // Frames a piercing bullet ignores rocks after passing through one, so
+    // it doesn't shred the fragments it just created
+    pierce_cooldown: i32,
+    // Whether the bullet has hit anything; one that runs out without a hit
+    // breaks the combo
+    scored: bool,
 }
 
+const PIERCE_COOLDOWN_FRAMES: i32 = 8;
+// How far a bullet must get from the muzzle before it can hit its own ship
+const SELF_HIT_ARMING_DISTANCE: f64 = 40.0;
+
 impl Bullet {
-    fn new(ship: &Ship) -> Self {
-        let speed = 10.0;
+    fn new(ship: &Ship, angle_offset: f64, piercing: bool, settings: &BulletSettings) -> Self {
+        let speed = settings.muzzle_speed;
+        let angle = ship.rotation + angle_offset;
         let start_position = Vector {
-            x: ship.position.x + 20.0 * ship.rotation.sin(),
-            y: ship.position.y - 20.0 * ship.rotation.cos(),
+            x: ship.position.x + 20.0 * angle.sin(),
+            y: ship.position.y - 20.0 * angle.cos(),
+        };
+        let mut velocity = Vector {
+            x: speed * angle.sin(),
+            y: -speed * angle.cos(),
         };
+        if settings.inherit_velocity {
+            velocity.x += ship.velocity.x;
+            velocity.y += ship.velocity.y;
+        }
         Bullet {
             position: start_position,
+            velocity,
+            lifetime: settings.lifetime,
+            range: settings.range,
+            travelled: 0.0,
+            wraps: settings.wrap,
+            piercing,
+            pierce_cooldown: 0,
+            scored: false,
+        }
+    }
+
+    // Shotgun pellets only carry a short distance
+    fn pellet(ship: &Ship, angle_offset: f64, piercing: bool, settings: &BulletSettings) -> Self {
+        let bullet = Bullet::new(ship, angle_offset, piercing, settings);
+        Bullet { lifetime: bullet.lifetime.min(18), range: bullet.range * 0.3, ..bullet }
+    }
+
+    // Saucer fire, starting just outside the hull
+    fn enemy(origin: &Vector, angle: f64, clearance: f64) -> Self {
+        Bullet {
+            position: Vector {
+                x: origin.x + clearance * angle.sin(),
+                y: origin.y - clearance * angle.cos(),
+            },
             velocity: Vector {
-                x: speed * ship.rotation.sin(),
-                y: -speed * ship.rotation.cos(),
+                x: ENEMY_BULLET_SPEED * angle.sin(),
+                y: -ENEMY_BULLET_SPEED * angle.cos(),
             },
-            lifetime: 60,
+            lifetime: 90,
+            range: f64::INFINITY,
+            travelled: 0.0,
+            wraps: true,
+            piercing: false,
+            pierce_cooldown: 0,
+            scored: false,
         }
     }
 
-    fn update(&mut self) -> bool {
-        self.position.x += self.velocity.x;
-        self.position.y += self.velocity.y;
-        
-        // Wrap around screen
+    fn update(&mut self, time_scale: f64) -> bool {
+        self.position.x += self.velocity.x * time_scale;
+        self.position.y += self.velocity.y * time_scale;
+        self.travelled += (self.velocity.x * self.velocity.x + self.velocity.y * self.velocity.y).sqrt() * time_scale;
+
+        // Wrap around screen, or leave it for good with wrapping off
+        let off_screen = self.position.x < 0.0 || self.position.x > 800.0 || self.position.y < 0.0 || self.position.y > 600.0;
+        if off_screen && !self.wraps {
+            return false;
+        }
         if self.position.x > 800.0 { self.position.x = 0.0; }
         if self.position.x < 0.0 { self.position.x = 800.0; }
         if self.position.y > 600.0 { self.position.y = 0.0; }
         if self.position.y < 0.0 { self.position.y = 600.0; }
 
+        self.pierce_cooldown = (self.pierce_cooldown - 1).max(0);
         self.lifetime -= 1;
-        self.lifetime > 0
+        self.lifetime > 0 && self.travelled < self.range
+    }
+
+    // Turn the bullet's path by `angle` radians without changing its speed
+    fn bend(&mut self, angle: f64) {
+        let (sin, cos) = angle.sin_cos();
+        self.velocity = Vector {
+            x: self.velocity.x * cos - self.velocity.y * sin,
+            y: self.velocity.x * sin + self.velocity.y * cos,
+        };
+    }
+
+    // Mirror the bullet off a round surface and lift it clear of it
+    fn reflect(&mut self, center: &Vector, radius: f64) {
+        let delta = wrap_delta(center, &self.position);
+        let distance = (delta.x * delta.x + delta.y * delta.y).sqrt().max(0.001);
+        let normal = Vector { x: delta.x / distance, y: delta.y / distance };
+        let along = self.velocity.x * normal.x + self.velocity.y * normal.y;
+        if along < 0.0 {
+            self.velocity.x -= 2.0 * along * normal.x;
+            self.velocity.y -= 2.0 * along * normal.y;
+        }
+        self.position.x = (center.x + normal.x * (radius + 1.0)).rem_euclid(WORLD_WIDTH);
+        self.position.y = (center.y + normal.y * (radius + 1.0)).rem_euclid(WORLD_HEIGHT);
     }
 
     fn draw(&self, ctx: &CanvasRenderingContext2d) {
@@ -107,65 +277,64 @@ impl Bullet {
     }
 }
 
-struct ShockWave {
-    position: Vector,
-    radius: f64,
-    max_radius: f64,
-    alpha: f64,
-    lifetime: i32,
+fn random_seed() -> u32 {
+    (Math::random() * u32::MAX as f64) as u32
 }
 
-impl ShockWave {
-    fn new(x: f64, y: f64) -> Self {
-        ShockWave {
-            position: Vector { x, y },
-            radius: 0.0,
-            max_radius: 150.0,
-            alpha: 1.0,
-            lifetime: 60,
+// Rocks for the start of a level, drawn from the run's random sequence
+fn level_asteroids(level: &LevelDefinition, wells: &[GravityWell]) -> Vec<Asteroid> {
+    let mut asteroids = Vec::new();
+    for _ in 0..level.asteroid_count {
+        // Keep new rocks out of the wells' grip
+        let mut x = rng::random() * 800.0;
+        let mut y = rng::random() * 600.0;
+        for _ in 0..10 {
+            let position = Vector { x, y };
+            if wells.iter().all(|well| well.keep_clear(&position, 80.0)) {
+                break;
+            }
+            x = rng::random() * 800.0;
+            y = rng::random() * 600.0;
         }
-    }
-
-    fn update(&mut self) -> bool {
-        self.radius += 3.0;
-        self.alpha = (self.lifetime as f64) / 60.0;
-        self.lifetime -= 1;
-        self.lifetime > 0 && self.radius < self.max_radius
-    }
 
-    fn draw(&self, ctx: &CanvasRenderingContext2d) {
-        ctx.save();
-        ctx.set_global_alpha(self.alpha);
-        ctx.set_stroke_style_str("#00ffff");
-        ctx.set_line_width(3.0);
-        
-        // Draw multiple expanding rings
-        for i in 0..3 {
-            let ring_radius = self.radius - (i as f64 * 15.0);
-            if ring_radius > 0.0 {
-                ctx.begin_path();
-                ctx.arc(self.position.x, self.position.y, ring_radius, 0.0, 2.0 * PI).unwrap();
-                ctx.stroke();
-            }
+        // Random chance between 1 in 100 to 1 in 300 for special rock
+        let special_chance = 100.0 + rng::random() * 200.0; // Random between 100-300
+        if rng::random() < 1.0 / special_chance {
+            asteroids.push(Asteroid::new_special(x, y, 40.0));
+        } else {
+            asteroids.push(Asteroid::of_material(x, y, level.choose_material()));
         }
-        
-        ctx.restore();
     }
+    asteroids
 }
 
+// Gives every rock, fragments included, an identity that survives the
+// asteroid list being reshuffled
+static NEXT_ASTEROID_ID: AtomicU32 = AtomicU32::new(0);
+
+// Speed at which the halves of a large rock fly apart, on top of the
+// parent's own velocity
+const SPLIT_SPEED: f64 = 1.5;
+
 struct Asteroid {
+    id: u32,
     position: Vector,
     velocity: Vector,
     size: f64,
     is_special: bool,
+    material: Material,
+    // Hits taken so far by a rock that needs several
+    damage: i32,
     pulse_time: f64,
+    outline: [f64; 8],
 }
 
 impl Asteroid {
     fn new(x: f64, y: f64, size: f64) -> Self {
         let speed = 2.0;
-        let angle = Math::random() * 2.0 * PI;
+        let angle = rng::random() * 2.0 * PI;
         Asteroid {
+            id: NEXT_ASTEROID_ID.fetch_add(1, Ordering::Relaxed),
             position: Vector { x, y },
             velocity: Vector {
                 x: speed * angle.sin(),
@@ -173,7 +342,10 @@ impl Asteroid {
             },
             size,
             is_special: false,
+            material: Material::Rock,
+            damage: 0,
             pulse_time: 0.0,
+            outline: std::array::from_fn(|_| 0.8 + 0.4 * rng::random()),
         }
     }
 
@@ -183,11 +355,17 @@ impl Asteroid {
         asteroid
     }
 
+    fn of_material(x: f64, y: f64, material: Material) -> Self {
+        let mut asteroid = Self::new(x, y, material.spawn_size());
+        asteroid.material = material;
+        asteroid
+    }
+
     fn update(&mut self, speed_multiplier: f64) {
         self.position.x += self.velocity.x * speed_multiplier;
         self.position.y += self.velocity.y * speed_multiplier;
         
-        if self.is_special {
+        if self.is_special || self.material == Material::Explosive {
             self.pulse_time += 0.1;
         }
         
@@ -198,19 +376,24 @@ impl Asteroid {
         if self.position.y < 0.0 { self.position.y = 600.0; }
     }
 
-    fn draw(&self, ctx: &CanvasRenderingContext2d) {
+    fn draw(&self, ctx: &CanvasRenderingContext2d, theme: &Theme) {
         ctx.save();
         
         if self.is_special {
-            // Special asteroid visual effects
-            let pulse = (self.pulse_time.sin() * 0.5 + 0.5) * 0.3 + 0.7;
-            ctx.set_global_alpha(pulse);
-            ctx.set_stroke_style_str("#ff0080");
-            ctx.set_line_width(2.0);
+            // Special asteroid visual effects; reduced flash holds them steady
+            let spin = if theme.reduced_flash {
+                0.0
+            } else {
+                let pulse = (self.pulse_time.sin() * 0.5 + 0.5) * 0.3 + 0.7;
+                ctx.set_global_alpha(pulse);
+                self.pulse_time
+            };
+            ctx.set_stroke_style_str(theme.special);
+            ctx.set_line_width(2.0 * theme.line_scale);
             
             // Draw electric arcs around the asteroid
             for i in 0..4 {
-                let angle = (i as f64) * PI * 0.5 + self.pulse_time;
+                let angle = (i as f64) * PI * 0.5 + spin;
                 let arc_x = self.position.x + (self.size + 10.0) * angle.cos();
                 let arc_y = self.position.y + (self.size + 10.0) * angle.sin();
                 ctx.begin_path();
@@ -218,15 +401,20 @@ impl Asteroid {
                 ctx.line_to(arc_x, arc_y);
                 ctx.stroke();
             }
+
+            if theme.hazard_markers {
+                self.draw_hazard_marker(ctx);
+            }
         } else {
-            ctx.set_stroke_style_str("white");
-            ctx.set_line_width(1.0);
+            ctx.set_stroke_style_str(self.material.color(theme));
+            let weight = if self.material == Material::Dense { 2.0 } else { 1.0 };
+            ctx.set_line_width(weight * theme.line_scale);
         }
         
         ctx.begin_path();
         for i in 0..8 {
             let angle = (i as f64) * PI / 4.0;
-            let radius = self.size * (0.8 + 0.4 * Math::random());
+            let radius = self.size * self.outline_point(i, theme);
             let x = self.position.x + radius * angle.cos();
             let y = self.position.y + radius * angle.sin();
             if i == 0 {
@@ -237,38 +425,183 @@ impl Asteroid {
         }
         ctx.close_path();
         ctx.stroke();
+
+        if !self.is_special {
+            self.draw_material_details(ctx, theme);
+        }
         
         ctx.restore();
     }
 
-    fn split(&self) -> Vec<Asteroid> {
-        if self.size < 15.0 || self.is_special {
+    // Each material has its own silhouette so they read by shape as well as colour
+    fn outline_point(&self, i: usize, theme: &Theme) -> f64 {
+        match self.material {
+            // Outlines crackle each frame unless reduced flash keeps them still
+            Material::Rock | Material::Explosive if !theme.reduced_flash => 0.8 + 0.4 * Math::random(),
+            Material::Rock | Material::Explosive | Material::Dense => self.outline[i],
+            // Smooth, nearly round hull
+            Material::Metallic => 0.95 + 0.1 * (self.outline[i] - 0.8),
+            // Sharp shards
+            Material::Ice => [1.1, 0.7][i % 2],
+            // Regular facets
+            Material::Crystal => 1.0,
+        }
+    }
+
+    fn draw_material_details(&self, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+        let (x, y) = (self.position.x, self.position.y);
+        match self.material {
+            // One crack running in from the rim for every hit taken
+            Material::Metallic => {
+                ctx.begin_path();
+                for crack in 0..self.damage as usize {
+                    let angle = (crack as f64 * 2.5 + self.outline[crack]) * PI / 4.0;
+                    let (sin, cos) = angle.sin_cos();
+                    ctx.move_to(x + cos * self.size, y + sin * self.size);
+                    ctx.line_to(x + cos * self.size * 0.5, y + sin * self.size * 0.5);
+                    ctx.line_to(x + (angle + 0.3).cos() * self.size * 0.25, y + (angle + 0.3).sin() * self.size * 0.25);
+                }
+                ctx.stroke();
+            }
+            // Facet lines from every corner to the centre, cracked when hit
+            Material::Crystal => {
+                ctx.begin_path();
+                for i in (0..8).step_by(2) {
+                    let angle = (i as f64) * PI / 4.0;
+                    ctx.move_to(x, y);
+                    ctx.line_to(x + angle.cos() * self.size, y + angle.sin() * self.size);
+                }
+                if self.damage > 0 {
+                    ctx.move_to(x - self.size * 0.6, y - self.size * 0.2);
+                    ctx.line_to(x + self.size * 0.1, y + self.size * 0.1);
+                    ctx.line_to(x + self.size * 0.5, y + self.size * 0.5);
+                }
+                ctx.stroke();
+            }
+            // Glowing core that throbs unless reduced flash holds it steady
+            Material::Explosive => {
+                let glow = if theme.reduced_flash { 0.3 } else { 0.25 + 0.1 * self.pulse_time.sin() };
+                ctx.set_fill_style_str(self.material.color(theme));
+                ctx.begin_path();
+                ctx.arc(x, y, self.size * glow, 0.0, 2.0 * PI).unwrap();
+                ctx.fill();
+            }
+            _ => {}
+        }
+    }
+
+    // Warning triangle so special rocks read by shape, not only by colour
+    fn draw_hazard_marker(&self, ctx: &CanvasRenderingContext2d) {
+        let side = self.size * 0.9;
+        let height = side * 0.866;
+        let (x, y) = (self.position.x, self.position.y);
+
+        ctx.begin_path();
+        ctx.move_to(x, y - height * 0.6);
+        ctx.line_to(x + side / 2.0, y + height * 0.4);
+        ctx.line_to(x - side / 2.0, y + height * 0.4);
+        ctx.close_path();
+        ctx.move_to(x, y - height * 0.25);
+        ctx.line_to(x, y + height * 0.1);
+        ctx.move_to(x, y + height * 0.2);
+        ctx.line_to(x, y + height * 0.26);
+        ctx.stroke();
+    }
+
+    fn mass(&self) -> f64 {
+        self.size * self.size * self.material.density()
+    }
+
+    // Fragments keep the parent's momentum and fly apart across the line of
+    // the hit, evenly spaced so the separation kicks cancel out. `impact` is
+    // the direction the hit was travelling; a zero vector picks one at random.
+    fn split(&self, impact: &Vector, pieces: usize) -> Vec<Asteroid> {
+        if self.size < 15.0 || self.is_special || pieces == 0 {
             return vec![];
         }
-        
-        vec![
-            Asteroid::new(self.position.x, self.position.y, self.size / 2.0),
-            Asteroid::new(self.position.x, self.position.y, self.size / 2.0),
-        ]
+
+        let size = self.size / 2.0;
+        let heading = if impact.x == 0.0 && impact.y == 0.0 {
+            rng::random() * 2.0 * PI
+        } else {
+            impact.y.atan2(impact.x)
+        };
+        // Smaller fragments are thrown apart faster
+        let kick = SPLIT_SPEED * (40.0 / size).sqrt() * self.material.fragment_speed();
+        // Far enough out that evenly spaced fragments don't overlap
+        let offset = size / (PI / pieces as f64).sin();
+
+        (0..pieces)
+            .map(|piece| {
+                let angle = heading + PI / 2.0 + piece as f64 * 2.0 * PI / pieces as f64;
+                let (sin, cos) = angle.sin_cos();
+                let mut fragment = Asteroid::new(
+                    (self.position.x + cos * offset).rem_euclid(WORLD_WIDTH),
+                    (self.position.y + sin * offset).rem_euclid(WORLD_HEIGHT),
+                    size,
+                );
+                fragment.material = self.material;
+                fragment.velocity = Vector { x: self.velocity.x + cos * kick, y: self.velocity.y + sin * kick };
+                fragment
+            })
+            .collect()
     }
 }
 
+// How long the lives counter flashes after an extra life, 1.5 seconds at 60 FPS
+const EXTRA_LIFE_FLASH_FRAMES: i32 = 90;
+
 #[wasm_bindgen]
 pub struct Game {
     ship: Ship,
     ctx: CanvasRenderingContext2d,
     asteroids: Vec<Asteroid>,
     bullets: Vec<Bullet>,
+    enemy_bullets: Vec<Bullet>,
+    missiles: Vec<Missile>,
+    mines: Vec<Mine>,
+    blasts: Vec<Blast>,
+    saucer: Option<Saucer>,
+    saucer_timer: i32,
+    boss: Option<Boss>,
+    power_ups: Vec<PowerUp>,
     shock_waves: Vec<ShockWave>,
-    game_over: bool,
+    gravity_wells: Vec<GravityWell>,
+    // Depth of the shock wave chain currently breaking rocks, or 0
+    shock_chain: i32,
+    particles: ParticleSystem,
+    state: GameState,
+    paused_from: GameState,
+    state_timer: i32,
+    events: Vec<GameEvent>,
     score: i32,
     lives: i32,
+    // Frames left of the lives counter flashing after an extra life
+    extra_life_flash: i32,
+    combo: Combo,
+    near_misses: NearMisses,
+    popups: Vec<ScorePopup>,
     level: i32,
-    respawn_timer: i32,
-    level_transition_timer: i32,
-    disruption_type: DisruptionType,
-    disruption_timer: i32,
-    uncontrollable_force: Vector,
+    disruptions: Disruptions,
+    settings: Settings,
+    i18n: Localizer,
+    menu: Menu,
+    high_scores: HighScores,
+    // Name typed on the high score page, kept as the default for the next one
+    player_name: String,
+    // Seeds the run's gameplay randomness and is recorded with its high
+    // score; `next_seed` replays a given run from the next new game
+    seed: u32,
+    next_seed: Option<u32>,
+    held_keys: HashSet<String>,
+    shield_held: bool,
+    touch: TouchControls,
+    mouse: MouseControls,
+    gamepad: GamepadControls,
+    document_hidden: Rc<Cell<bool>>,
+    visibility_listener: Option<Closure<dyn FnMut()>>,
+    // One context for every sound; browsers cap how many a page may open
+    audio: RefCell<Option<web_sys::AudioContext>>,
 }
 
 #[wasm_bindgen]
@@ -280,49 +613,185 @@ impl Game {
             .unwrap()
             .dyn_into::<CanvasRenderingContext2d>()?;
 
+        let settings = Settings::new();
         let mut game = Game {
             ship: Ship::new(400.0, 300.0),
             ctx,
             asteroids: Vec::new(),
             bullets: Vec::new(),
+            enemy_bullets: Vec::new(),
+            missiles: Vec::new(),
+            mines: Vec::new(),
+            blasts: Vec::new(),
+            saucer: None,
+            saucer_timer: 0,
+            boss: None,
+            power_ups: Vec::new(),
             shock_waves: Vec::new(),
-            game_over: false,
+            gravity_wells: Vec::new(),
+            shock_chain: 0,
+            particles: ParticleSystem::new(settings.particles),
+            state: GameState::Title,
+            paused_from: GameState::Playing,
+            state_timer: 0,
+            events: Vec::new(),
             score: 0,
             lives: 3,
+            extra_life_flash: 0,
+            combo: Combo::new(),
+            near_misses: NearMisses::new(),
+            popups: Vec::new(),
             level: 1,
-            respawn_timer: 0,
-            level_transition_timer: 0,
-            disruption_type: DisruptionType::None,
-            disruption_timer: 0,
-            uncontrollable_force: Vector { x: 0.0, y: 0.0 },
+            disruptions: Disruptions::new(),
+            settings,
+            i18n: Localizer::new(),
+            menu: Menu::new(),
+            high_scores: HighScores::load(default_store()),
+            player_name: String::new(),
+            seed: 0,
+            next_seed: None,
+            held_keys: HashSet::new(),
+            shield_held: false,
+            touch: TouchControls::new(),
+            mouse: MouseControls::new(),
+            gamepad: GamepadControls::new(),
+            document_hidden: Rc::new(Cell::new(false)),
+            visibility_listener: None,
+            audio: RefCell::new(None),
         };
 
+        game.watch_visibility()?;
+        // The rocks drifting behind the title differ from load to load
+        rng::seed(random_seed());
         game.spawn_level_asteroids();
         Ok(game)
     }
 
+    // Remember when the page is hidden so the next update can pause; animation
+    // frames stop while hidden, so this has to come from the DOM event
+    fn watch_visibility(&mut self) -> Result<(), JsValue> {
+        let document = match web_sys::window().and_then(|win| win.document()) {
+            Some(document) => document,
+            None => return Ok(()),
+        };
+
+        let hidden = self.document_hidden.clone();
+        let watched = document.clone();
+        let listener = Closure::<dyn FnMut()>::new(move || {
+            if watched.hidden() {
+                hidden.set(true);
+            }
+        });
+        document.add_event_listener_with_callback("visibilitychange", listener.as_ref().unchecked_ref())?;
+        self.visibility_listener = Some(listener);
+        Ok(())
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        if self.events.len() >= MAX_QUEUED_EVENTS {
+            self.events.remove(0);
+        }
+        self.events.push(event);
+    }
+
+    fn transition(&mut self, next: GameState) -> bool {
+        if !self.state.can_transition_to(next) {
+            // Callers only ask for transitions they expect to be allowed, so
+            // a rejected one means the game has lost track of its state
+            let message = format!("rejected state transition {} -> {}", self.state.name(), next.name());
+            web_sys::console::warn_1(&JsValue::from_str(&message));
+            return false;
+        }
+
+        let previous = self.state;
+        self.exit_state(previous, next);
+        self.state = next;
+        self.enter_state(next, previous);
+        true
+    }
+
+    fn exit_state(&mut self, state: GameState, next: GameState) {
+        // Each level's rocks are spawned once, by whatever starts it: the end
+        // of the level transition or a new game
+        if state == GameState::Paused && next != GameState::Paused {
+            self.play_sound("resume-sound");
+        }
+        self.emit(GameEvent::StateExited(state));
+    }
+
+    fn enter_state(&mut self, state: GameState, previous: GameState) {
+        // Resuming picks up where the paused state left off
+        if previous != GameState::Paused {
+            match state {
+                GameState::Title => {
+                    self.menu.open_root(MenuPage::Main);
+                    self.release_inputs();
+                    self.bullets.clear();
+                    self.clear_ordnance();
+                    self.shock_waves.clear();
+                    self.gravity_wells.clear();
+                    self.boss = None;
+                    self.particles.clear();
+                    self.popups.clear();
+                    self.power_ups.clear();
+                    self.clear_saucers();
+                }
+                GameState::Paused => {
+                    self.paused_from = previous;
+                    self.menu.open_root(MenuPage::Pause);
+                    self.release_inputs();
+                    self.play_sound("pause-sound");
+                }
+                GameState::Respawning => {
+                    // Respawn ship in center with temporary invulnerability
+                    self.ship = Ship::new(400.0, 300.0);
+                    self.state_timer = 180; // 3 seconds at 60 FPS
+                    self.enemy_bullets.clear();
+                }
+                GameState::LevelTransition => {
+                    self.state_timer = 300; // 5 seconds at 60 FPS
+                    self.power_ups.clear();
+                    self.particles.clear();
+                    self.popups.clear();
+                    self.combo.reset();
+                    self.clear_ordnance();
+                    self.ship.weapon.restock();
+                    self.clear_saucers();
+                    self.play_sound("level-complete-sound");
+                }
+                GameState::GameOver => {
+                    self.menu.open_root(MenuPage::GameOver);
+                    // A score that makes the table asks for a name first
+                    if self.high_scores.qualifies(self.score) {
+                        self.menu.open_root(MenuPage::NameEntry);
+                    }
+                    self.release_inputs();
+                    self.play_sound("game-over-sound");
+                }
+                GameState::Playing => {}
+            }
+        }
+        self.emit(GameEvent::StateEntered(state));
+    }
+
     fn spawn_level_asteroids(&mut self) {
         self.asteroids.clear();
-        let asteroid_count = 4 + (self.level - 1) / 4; // Add extra asteroid every 4 levels
-        
-        for _ in 0..asteroid_count {
-            let x = Math::random() * 800.0;
-            let y = Math::random() * 600.0;
-            
-            // Random chance between 1 in 100 to 1 in 300 for special rock
-            let special_chance = 100.0 + Math::random() * 200.0; // Random between 100-300
-            if Math::random() < 1.0 / special_chance {
-                self.asteroids.push(Asteroid::new_special(x, y, 40.0));
-            } else {
-                self.asteroids.push(Asteroid::new(x, y, 40.0));
-            }
+        let mut level = LevelDefinition::for_level(self.level);
+        let wells = std::mem::take(&mut level.wells);
+        self.gravity_wells = if self.settings.gravity_wells { wells } else { Vec::new() };
+        self.boss = level.boss.map(Boss::new);
+        if let Some(boss) = &self.boss {
+            self.emit(GameEvent::BossSpawned(boss.definition.name));
+            self.play_sound("boss-warning-sound");
         }
+        
+        self.asteroids = level_asteroids(&level, &self.gravity_wells);
     }
 
     fn get_speed_multiplier(&self) -> f64 {
         let base_speed = 1.0;
         let level_factor = ((self.level - 1) % 3) as f64 * 0.3; // Speed increases within each 3-level cycle
-        base_speed + level_factor
+        (base_speed + level_factor) * self.settings.difficulty.speed_factor()
     }
 
     fn get_score_multiplier(&self) -> i32 {
@@ -330,312 +799,2588 @@ impl Game {
     }
 
     pub fn update(&mut self) {
-        if self.game_over {
-            return;
+        if self.document_hidden.replace(false) {
+            self.pause();
         }
 
-        // Handle level transition
-        if self.level_transition_timer > 0 {
-            self.level_transition_timer -= 1;
-            if self.level_transition_timer == 0 {
-                self.spawn_level_asteroids();
-            }
-            return;
+        // Debris keeps drifting behind the game over menu
+        if self.state != GameState::Paused {
+            self.particles.update();
+            self.popups.retain_mut(|popup| popup.update());
+            self.extra_life_flash = (self.extra_life_flash - 1).max(0);
         }
 
-        // Handle disruption timer
-        if self.disruption_timer > 0 {
-            self.disruption_timer -= 1;
-            if self.disruption_timer == 0 {
-                self.disruption_type = DisruptionType::None;
-                self.uncontrollable_force = Vector { x: 0.0, y: 0.0 };
+        // Inputs below raise the shield again each frame they are held
+        self.ship.shield.requested = self.shield_held;
+        self.apply_gamepad_input();
+
+        match self.state {
+            GameState::Title => {
+                // Let the rocks drift behind the title screen
+                self.move_asteroids();
+                return;
+            }
+            GameState::Paused | GameState::GameOver => return,
+            GameState::LevelTransition => {
+                self.state_timer -= 1;
+                if self.state_timer <= 0 {
+                    self.spawn_level_asteroids();
+                    self.transition(GameState::Playing);
+                }
+                return;
             }
+            GameState::Playing | GameState::Respawning => {}
+        }
+
+        if self.combo.tick() {
+            self.emit(GameEvent::Combo(1));
+        }
+
+        // Handle disruption timers
+        let (ended, started) = self.disruptions.tick();
+        for kind in ended {
+            self.emit(GameEvent::DisruptionEnded(kind));
+        }
+        for kind in started {
+            self.disruption_started(kind);
         }
 
         // Apply uncontrollable force if active
-        if self.disruption_type == DisruptionType::Uncontrollable {
-            self.ship.velocity.x += self.uncontrollable_force.x;
-            self.ship.velocity.y += self.uncontrollable_force.y;
+        if self.disruptions.has(DisruptionType::Uncontrollable) {
+            self.ship.velocity.x += self.disruptions.uncontrollable_force.x;
+            self.ship.velocity.y += self.disruptions.uncontrollable_force.y;
         }
 
-        // Decrease respawn timer if active
-        if self.respawn_timer > 0 {
-            self.respawn_timer -= 1;
+        if self.disruptions.has(DisruptionType::GravityPulse) {
+            for asteroid in &mut self.asteroids {
+                let pull = gravity_pull(&self.disruptions.gravity_origin, &asteroid.position);
+                asteroid.velocity.x += pull.x;
+                asteroid.velocity.y += pull.y;
+            }
         }
 
-        self.ship.update();
-        
-        // Check ship-asteroid collisions only if not in respawn period
-        if self.respawn_timer == 0 {
-            for asteroid in &self.asteroids {
-                let dx = self.ship.position.x - asteroid.position.x;
-                let dy = self.ship.position.y - asteroid.position.y;
-                if (dx * dx + dy * dy).sqrt() < asteroid.size + 10.0 {
-                    self.lives -= 1;
-                    play_sound("explosion-sound");
-                    
-                    if self.lives <= 0 {
-                        self.game_over = true;
-                        return;
-                    } else {
-                        // Respawn ship in center with temporary invulnerability
-                        self.ship = Ship::new(400.0, 300.0);
-                        self.respawn_timer = 180; // 3 seconds at 60 FPS
+        self.apply_gravity_wells();
+
+        self.apply_keyboard_input();
+        self.apply_touch_input();
+        self.apply_mouse_input();
+
+        self.ship.weapon.tick(&self.settings.weapon);
+        // Fall back to the blaster once a weapon runs dry
+        if !self.ship.weapon.has_ammo(self.ship.weapon.current) {
+            self.select(WeaponKind::Blaster);
+        }
+        for kind in self.ship.power_ups.tick() {
+            self.emit(GameEvent::PowerUpExpired(kind));
+        }
+
+        self.ship.shield.sustained = self.ship.power_ups.has(PowerUpKind::Shield);
+        match self.ship.shield.update(self.controls_locked()) {
+            Some(ShieldChange::Raised) => self.play_sound("shield-up-sound"),
+            Some(ShieldChange::Depleted) => self.emit(GameEvent::ShieldDepleted),
+            None => {}
+        }
+
+        // Count down the respawn invulnerability
+        if self.state == GameState::Respawning {
+            self.state_timer -= 1;
+            if self.state_timer <= 0 {
+                self.transition(GameState::Playing);
+            }
+        }
+
+        if let Some(step) = self.ship.hyperspace.tick() {
+            match step {
+                WarpStep::Jump => {
+                    if !self.complete_jump() {
                         return;
                     }
                 }
+                WarpStep::Landed => {}
+            }
+        }
+
+        // The ship is out of normal space for the whole warp
+        if !self.ship.hyperspace.is_warping() {
+            self.ship.update();
+        }
+        
+        // Check ship-asteroid collisions only if not in respawn period
+        if self.state == GameState::Playing && !self.ship.hyperspace.is_warping() && self.ship.shield.active {
+            self.deflect_asteroids();
+        } else if self.state == GameState::Playing && !self.ship.hyperspace.is_warping() {
+            // Measured across the screen edge, like the near-miss check, so a
+            // rock can't pass through the ship while it straddles the edge
+            let ship_hit = self
+                .asteroids
+                .iter()
+                .any(|asteroid| wrap_distance(&self.ship.position, &asteroid.position) < asteroid.size + 10.0);
+
+            if ship_hit {
+                self.destroy_ship();
+                return;
             }
         }
         
         // Update bullets and remove dead ones
-        self.bullets.retain_mut(|bullet| bullet.update());
+        if self.disruptions.has(DisruptionType::MagneticField) {
+            let turn = MAGNETIC_TURN * self.disruptions.magnetic_direction;
+            for bullet in self.bullets.iter_mut().chain(self.enemy_bullets.iter_mut()) {
+                bullet.bend(turn);
+            }
+        }
+        let mut missed = false;
+        self.bullets.retain_mut(|bullet| {
+            let alive = bullet.update(1.0);
+            missed |= !alive && !bullet.scored;
+            alive
+        });
+        if missed {
+            self.break_combo();
+        }
+        let asteroids = &self.asteroids;
+        self.missiles.retain_mut(|missile| missile.update(asteroids));
+        self.mines.retain_mut(|mine| mine.update());
+        self.blasts.retain_mut(|blast| blast.update());
         
-        // Update asteroids with speed multiplier
-        let speed_multiplier = self.get_speed_multiplier();
-        for asteroid in &mut self.asteroids {
-            asteroid.update(speed_multiplier);
+        self.move_asteroids();
+        if self.settings.collisions.enabled {
+            resolve_asteroid_collisions(&mut self.asteroids, self.settings.collisions.restitution);
         }
 
         // Update shock waves
         self.shock_waves.retain_mut(|wave| wave.update());
+        self.resolve_shock_waves();
 
-        // Check bullet collisions
-        let mut new_asteroids = Vec::new();
-        let mut bullets_to_remove = Vec::new();
-        let mut asteroids_to_remove = Vec::new();
-        
-        for (bullet_idx, bullet) in self.bullets.iter().enumerate() {
-            for (asteroid_idx, asteroid) in self.asteroids.iter().enumerate() {
-                let dx = bullet.position.x - asteroid.position.x;
-                let dy = bullet.position.y - asteroid.position.y;
-                let distance = (dx * dx + dy * dy).sqrt();
-
-                if distance < asteroid.size {
-                    bullets_to_remove.push(bullet_idx);
-                    asteroids_to_remove.push(asteroid_idx);
-                    
-                    let score_multiplier = self.get_score_multiplier();
-                    
-                    if asteroid.is_special {
-                        // Special asteroid hit
-                        self.score += 100 * score_multiplier;
-                        self.shock_waves.push(ShockWave::new(asteroid.position.x, asteroid.position.y));
-                        play_sound("special-explosion-sound");
-                        
-                        // Apply random disruption
-                        self.disruption_timer = 120; // 2 seconds at 60 FPS
-                        let random_disruption = Math::random();
-                        if random_disruption < 0.33 {
-                            self.disruption_type = DisruptionType::Disabled;
-                        } else if random_disruption < 0.66 {
-                            self.disruption_type = DisruptionType::Scrambled;
-                        } else {
-                            self.disruption_type = DisruptionType::Uncontrollable;
-                            // Random uncontrollable force
-                            let angle = Math::random() * 2.0 * PI;
-                            self.uncontrollable_force = Vector {
-                                x: 0.2 * angle.sin(),
-                                y: -0.2 * angle.cos(),
-                            };
-                        }
-                    } else {
-                        // Regular asteroid scoring
-                        self.score += if asteroid.size >= 40.0 {
-                            20 * score_multiplier  // Large asteroid
-                        } else if asteroid.size >= 20.0 {
-                            50 * score_multiplier  // Medium asteroid
-                        } else {
-                            100 * score_multiplier  // Small asteroid
-                        };
-                        
-                        new_asteroids.extend(asteroid.split());
-                    }
-                    break;
-                }
-            }
-        }
+        let time_scale = self.time_scale();
+        self.enemy_bullets.retain_mut(|bullet| bullet.update(time_scale));
+        self.update_saucer();
+        self.update_boss();
+        self.power_ups.retain_mut(|power_up| power_up.update());
+        self.collect_power_ups();
 
-        // Remove asteroids in reverse order to maintain correct indices
-        asteroids_to_remove.sort_unstable();
-        asteroids_to_remove.reverse();
-        for asteroid_idx in asteroids_to_remove {
-            self.asteroids.remove(asteroid_idx);
+        if self.swallow_into_wells() {
+            return;
         }
 
-        // Remove bullets in reverse order to maintain correct indices
-        bullets_to_remove.sort_unstable();
-        bullets_to_remove.reverse();
-        for bullet_idx in bullets_to_remove {
-            self.bullets.remove(bullet_idx);
+        self.resolve_bullet_hits();
+        self.fire_laser();
+        self.resolve_missile_hits();
+        self.detonate_mines();
+        if self.resolve_boss_hits()
+            || self.resolve_saucer_hits()
+            || self.resolve_enemy_bullet_hits()
+            || self.resolve_self_hits()
+        {
+            return;
         }
-
-        // Add new asteroids from splits
-        self.asteroids.extend(new_asteroids);
+        self.check_near_misses();
 
         // Check if level is complete
-        if self.asteroids.is_empty() && self.level_transition_timer == 0 {
+        if self.asteroids.is_empty() && self.boss.is_none() {
             self.level += 1;
-            self.level_transition_timer = 300; // 5 seconds at 60 FPS
-            play_sound("level-complete-sound");
+            self.transition(GameState::LevelTransition);
         }
     }
 
-    pub fn render(&self) {
-        self.ctx.clear_rect(0.0, 0.0, 800.0, 600.0);
-        self.ctx.set_stroke_style_str("white");
-        self.ctx.set_fill_style_str("white");
-        
-        // Draw score and level at top left
-        self.ctx.set_font("24px Arial");
-        self.ctx.set_text_align("left");
-        self.ctx.fill_text(&format!("Score: {}", self.score), 20.0, 40.0).unwrap();
-        self.ctx.fill_text(&format!("Level: {}", self.level), 20.0, 70.0).unwrap();
-        
-        // Draw lives at top right
-        self.ctx.set_text_align("right");
-        self.ctx.fill_text(&format!("Lives: {}", self.lives), 780.0, 40.0).unwrap();
-
-        // Draw disruption status
-        if self.disruption_timer > 0 {
-            self.ctx.set_text_align("center");
-            self.ctx.set_fill_style_str("#ff0080");
-            let disruption_text = match self.disruption_type {
-                DisruptionType::Disabled => "SYSTEMS DISABLED",
-                DisruptionType::Scrambled => "CONTROLS SCRAMBLED",
-                DisruptionType::Uncontrollable => "SHIP UNSTABLE",
-                DisruptionType::None => "",
-            };
-            self.ctx.fill_text(disruption_text, 400.0, 100.0).unwrap();
-            self.ctx.set_fill_style_str("white");
-        }
-        
-        // Draw level transition countdown
-        if self.level_transition_timer > 0 {
-            self.ctx.set_font("48px Arial");
-            self.ctx.set_text_align("center");
-            self.ctx.fill_text(&format!("LEVEL {}", self.level), 400.0, 280.0).unwrap();
-            self.ctx.set_font("24px Arial");
-            let countdown = (self.level_transition_timer / 60) + 1;
-            self.ctx.fill_text(&format!("Starting in {}...", countdown), 400.0, 320.0).unwrap();
-            self.ctx.fill_text("Get Ready!", 400.0, 350.0).unwrap();
+    // Every well pulls on the ship, all bullets and the rocks
+    fn apply_gravity_wells(&mut self) {
+        if self.gravity_wells.is_empty() {
             return;
         }
-        
-        if !self.game_over {
-            // Make ship blink during respawn period
-            if self.respawn_timer == 0 || self.respawn_timer % 20 > 10 {
-                self.ship.draw(&self.ctx);
-            }
+        for well in &mut self.gravity_wells {
+            well.update();
         }
-        
-        for bullet in &self.bullets {
-            bullet.draw(&self.ctx);
+
+        let pull = |wells: &[GravityWell], position: &Vector| {
+            wells.iter().fold(Vector { x: 0.0, y: 0.0 }, |total, well| {
+                let acceleration = well.acceleration(position);
+                Vector { x: total.x + acceleration.x, y: total.y + acceleration.y }
+            })
+        };
+
+        if !self.ship.hyperspace.is_warping() {
+            let acceleration = pull(&self.gravity_wells, &self.ship.position);
+            self.ship.velocity.x += acceleration.x;
+            self.ship.velocity.y += acceleration.y;
         }
-        
-        for asteroid in &self.asteroids {
-            asteroid.draw(&self.ctx);
+        for bullet in self.bullets.iter_mut().chain(self.enemy_bullets.iter_mut()) {
+            let acceleration = pull(&self.gravity_wells, &bullet.position);
+            bullet.velocity.x += acceleration.x;
+            bullet.velocity.y += acceleration.y;
         }
+        for asteroid in &mut self.asteroids {
+            let acceleration = pull(&self.gravity_wells, &asteroid.position);
+            asteroid.velocity.x += acceleration.x;
+            asteroid.velocity.y += acceleration.y;
+            let speed = (asteroid.velocity.x * asteroid.velocity.x + asteroid.velocity.y * asteroid.velocity.y).sqrt();
+            if speed > MAX_ASTEROID_SPEED {
+                asteroid.velocity.x *= MAX_ASTEROID_SPEED / speed;
+                asteroid.velocity.y *= MAX_ASTEROID_SPEED / speed;
+            }
+        }
+    }
 
-        for shock_wave in &self.shock_waves {
-            shock_wave.draw(&self.ctx);
+    // Anything that crosses a well's horizon is gone for good: rocks vanish
+    // without splitting or scoring. Returns true if the ship was swallowed.
+    fn swallow_into_wells(&mut self) -> bool {
+        if self.gravity_wells.is_empty() {
+            return false;
         }
+        let wells = &self.gravity_wells;
+        let swallowed = |position: &Vector, size: f64| wells.iter().any(|well| well.swallows(position, size));
 
-        if self.game_over {
-            self.ctx.set_font("48px Arial");
-            self.ctx.set_text_align("center");
-            self.ctx.fill_text("GAME OVER", 400.0, 280.0).unwrap();
-            self.ctx.set_font("24px Arial");
-            self.ctx.fill_text(&format!("Final Score: {}", self.score), 400.0, 320.0).unwrap();
-            self.ctx.fill_text(&format!("Level Reached: {}", self.level), 400.0, 350.0).unwrap();
-            self.ctx.fill_text("Press R or click New Game to restart", 400.0, 380.0).unwrap();
+        self.bullets.retain(|bullet| !swallowed(&bullet.position, 0.0));
+        self.enemy_bullets.retain(|bullet| !swallowed(&bullet.position, 0.0));
+        let count_before = self.asteroids.len();
+        self.asteroids.retain(|asteroid| !swallowed(&asteroid.position, asteroid.size));
+        let rocks_lost = self.asteroids.len() < count_before;
+
+        // A respawning ship drifts over the well unharmed; it has to be clear
+        // by the time its invulnerability runs out
+        let ship_lost = self.ship_vulnerable() && swallowed(&self.ship.position, 10.0);
+
+        if rocks_lost {
+            self.play_sound("swallow-sound");
         }
+        if ship_lost {
+            self.emit(GameEvent::ShipSwallowed);
+            self.destroy_ship();
+        }
+        ship_lost
     }
 
-    pub fn rotate(&mut self, angle: f64) {
-        if self.disruption_type == DisruptionType::Disabled {
-            return;
+    // Points are shown floating up from `at`
+    fn award_points(&mut self, base_points: i32, at: &Vector) {
+        let previous = self.score;
+        let points = base_points * self.get_score_multiplier() * self.combo.multiplier();
+        self.score += points;
+        self.popups.push(ScorePopup::new(at, self.i18n.format("popup-points", &[("points", points as i64)])));
+        self.award_extra_lives(previous);
+    }
+
+    // A player hit that extends the combo
+    fn register_hit(&mut self) {
+        if self.combo.hit() {
+            self.emit(GameEvent::Combo(self.combo.multiplier()));
+            self.play_sound("combo-sound");
         }
-        
-        let actual_angle = if self.disruption_type == DisruptionType::Scrambled {
-            -angle // Reverse controls
-        } else {
-            angle
-        };
-        
-        self.ship.rotation += actual_angle;
     }
 
-    pub fn thrust(&mut self) {
-        if self.disruption_type == DisruptionType::Disabled {
-            return;
+    fn break_combo(&mut self) {
+        if self.combo.reset() {
+            self.emit(GameEvent::Combo(1));
         }
-        
-        let thrust = 0.1;
-        let (thrust_x, thrust_y) = if self.disruption_type == DisruptionType::Scrambled {
-            // Scrambled: thrust goes sideways
-            (thrust * self.ship.rotation.cos(), thrust * self.ship.rotation.sin())
-        } else {
-            // Normal thrust
-            (thrust * self.ship.rotation.sin(), -thrust * self.ship.rotation.cos())
-        };
-        
-        self.ship.velocity.x += thrust_x;
-        self.ship.velocity.y += thrust_y;
-        play_sound("thrust-sound");
     }
 
-    pub fn shoot(&mut self) {
-        if self.disruption_type == DisruptionType::Disabled {
+    // Rocks that slip past the ship's hull without touching it earn a bonus
+    // once they've gone by. The shield doesn't count as a close call.
+    fn check_near_misses(&mut self) {
+        if !self.ship_vulnerable() || self.ship.shield.active {
+            self.near_misses.clear();
             return;
         }
-        
-        self.bullets.push(Bullet::new(&self.ship));
-        play_sound("shoot-sound");
-    }
+        let ship = &self.ship.position;
+        let grazing: Vec<u32> = self
+            .asteroids
+            .iter()
+            .filter(|asteroid| wrap_distance(ship, &asteroid.position) < asteroid.size + 10.0 + NEAR_MISS_MARGIN)
+            .map(|asteroid| asteroid.id)
+            .collect();
 
-    pub fn reset(&mut self) {
-        self.game_over = false;
-        self.ship = Ship::new(400.0, 300.0);
-        self.bullets.clear();
-        self.asteroids.clear();
-        self.shock_waves.clear();
-        self.score = 0;
-        self.lives = 3;
-        self.level = 1;
-        self.respawn_timer = 0;
-        self.level_transition_timer = 0;
-        self.disruption_type = DisruptionType::None;
-        self.disruption_timer = 0;
-        self.uncontrollable_force = Vector { x: 0.0, y: 0.0 };
-        
-        self.spawn_level_asteroids();
+        // Rocks shot down mid-pass don't count
+        for id in self.near_misses.update(grazing) {
+            if self.asteroids.iter().any(|asteroid| asteroid.id == id) {
+                let position = self.ship.position.clone();
+                self.award_points(NEAR_MISS_BONUS, &position);
+                self.popups.push(ScorePopup::new(
+                    &Vector { x: position.x, y: position.y - 20.0 },
+                    self.i18n.text("popup-near-miss"),
+                ));
+                self.emit(GameEvent::NearMiss);
+                self.play_sound("near-miss-sound");
+            }
+        }
     }
 
-    pub fn is_game_over(&self) -> bool {
-        self.game_over
+    // One life for every threshold crossed since `previous`, so a big award
+    // at a high multiplier can earn several at once
+    fn award_extra_lives(&mut self, previous: i32) {
+        let interval = self.settings.extra_lives.interval;
+        if interval > 0 {
+            self.grant_lives(self.score / interval - previous / interval);
+        }
     }
 
-    pub fn get_score(&self) -> i32 {
-        self.score
+    // Every extra life, from points or the power-up, goes through here so the
+    // cap applies to all of them
+    fn grant_lives(&mut self, count: i32) {
+        let max_lives = self.settings.extra_lives.max_lives;
+        let mut earned = false;
+        for _ in 0..count {
+            if max_lives > 0 && self.lives >= max_lives {
+                break;
+            }
+            self.lives += 1;
+            earned = true;
+            self.emit(GameEvent::ExtraLife(self.lives));
+        }
+        if earned {
+            self.extra_life_flash = EXTRA_LIFE_FLASH_FRAMES;
+            self.play_sound("extra-life-sound");
+        }
     }
 
-    pub fn get_level(&self) -> i32 {
-        self.level
-    }
-}
+    // Each player bullet hits at most one rock, and each rock is only hit
+    // once however many bullets reach it in the same frame
+    fn resolve_bullet_hits(&mut self) {
+        let mut hits: Vec<(usize, u32, Vector)> = Vec::new();
+        for (bullet_idx, bullet) in self.bullets.iter().enumerate() {
+            if bullet.pierce_cooldown > 0 {
+                continue;
+            }
+            let target = self.asteroids.iter().find(|asteroid| {
+                wrap_distance(&bullet.position, &asteroid.position) < asteroid.size
+                    && !hits.iter().any(|(_, id, _)| *id == asteroid.id)
+            });
+            if let Some(asteroid) = target {
+                hits.push((bullet_idx, asteroid.id, bullet.velocity.clone()));
+            }
+        }
 
-fn play_sound(sound_id: &str) {
-    let _window = match web_sys::window() {
-        Some(win) => win,
-        None => return,
-    };
+        // Crystal turns bullets back and piercing bullets carry on; remove the
+        // rest in reverse order to keep the remaining indices valid
+        let mut bullet_hits: Vec<(usize, u32)> = hits.iter().map(|(bullet_idx, id, _)| (*bullet_idx, *id)).collect();
+        bullet_hits.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+        for (bullet_idx, id) in bullet_hits {
+            self.particles.impact_sparks(&self.bullets[bullet_idx].position);
+            self.bullets[bullet_idx].scored = true;
+            let crystal = self
+                .asteroids
+                .iter()
+                .find(|asteroid| asteroid.id == id && asteroid.material == Material::Crystal)
+                .map(|asteroid| (asteroid.position.clone(), asteroid.size));
+            if let Some((center, radius)) = crystal {
+                self.bullets[bullet_idx].reflect(&center, radius);
+            } else if self.bullets[bullet_idx].piercing {
+                self.bullets[bullet_idx].pierce_cooldown = PIERCE_COOLDOWN_FRAMES;
+            } else {
+                self.bullets.remove(bullet_idx);
+            }
+        }
 
-    // Create AudioContext
-    let audio_context = match web_sys::AudioContext::new() {
-        Ok(ctx) => ctx,
-        Err(_) => return,
-    };
+        // Rocks are found by id, since an explosive one can take others with it
+        for (_, id, impact) in hits {
+            if let Some(index) = self.asteroids.iter().position(|asteroid| asteroid.id == id) {
+                self.hit_asteroid(index, true, &impact);
+            }
+        }
+    }
+
+    // Bullets, beams and missiles wear tough rocks down before breaking them
+    fn hit_asteroid(&mut self, index: usize, by_player: bool, impact: &Vector) {
+        if by_player {
+            self.register_hit();
+        }
+        let asteroid = &mut self.asteroids[index];
+        asteroid.damage += 1;
+        if asteroid.damage < asteroid.material.hit_points(asteroid.size) {
+            if let Some(sound) = asteroid.material.hit_sound() {
+                self.play_sound(sound);
+            }
+            return;
+        }
+        self.destroy_asteroid(index, by_player, impact);
+    }
+
+    // Destroys whichever of the given rocks are still around, each thrown
+    // outward from `origin`
+    fn destroy_caught(&mut self, ids: &[u32], origin: &Vector, by_player: bool) {
+        for id in ids {
+            if let Some(index) = self.asteroids.iter().position(|asteroid| asteroid.id == *id) {
+                let outward = wrap_delta(origin, &self.asteroids[index].position);
+                self.destroy_asteroid(index, by_player, &outward);
+            }
+        }
+    }
+
+    // An explosive rock takes every rock in the blast with it, and explosive
+    // neighbours go off in turn
+    fn explode_rock(&mut self, position: &Vector, by_player: bool) {
+        self.blasts.push(Blast::new(position.clone()));
+        let caught: Vec<u32> = self
+            .asteroids
+            .iter()
+            .filter(|asteroid| wrap_distance(position, &asteroid.position) < MINE_BLAST_RADIUS + asteroid.size)
+            .map(|asteroid| asteroid.id)
+            .collect();
+        self.destroy_caught(&caught, position, by_player);
+    }
+
+    // Shared by everything that can break a rock. Fragments are appended, so
+    // indices below `index` stay valid unless the rock was explosive. Only
+    // the player scores or triggers a special rock's disruption. `impact` is
+    // the direction the hit travelled.
+    fn destroy_asteroid(&mut self, index: usize, by_player: bool, impact: &Vector) {
+        let asteroid = self.asteroids.remove(index);
+        self.particles.asteroid_explosion(&asteroid.position, asteroid.size);
+
+        if by_player && rng::random() < self.settings.power_ups.drop_chance(self.level) {
+            self.power_ups.push(PowerUp::new(PowerUpKind::random(), asteroid.position.x, asteroid.position.y));
+        }
+
+        if asteroid.is_special {
+            let chain = self.shock_chain + 1;
+            self.shock_waves.push(ShockWave::new(asteroid.position.x, asteroid.position.y, by_player, chain));
+            self.particles.electric_burst(&asteroid.position, &self.settings.accessibility.theme());
+            self.play_sound("special-explosion-sound");
+            if by_player {
+                self.award_points(100, &asteroid.position);
+                self.apply_random_disruption(&asteroid.position);
+            }
+        } else {
+            if by_player {
+                let base = if asteroid.size >= 40.0 {
+                    20 // Large asteroid
+                } else if asteroid.size >= 20.0 {
+                    50 // Medium asteroid
+                } else {
+                    100 // Small asteroid
+                };
+                self.award_points(base * asteroid.material.score_factor(), &asteroid.position);
+            }
+            if let Some(sound) = asteroid.material.break_sound() {
+                self.play_sound(sound);
+            }
+            let pieces = if self.settings.three_way_split && asteroid.material == Material::Rock && asteroid.size >= 40.0 {
+                3
+            } else {
+                asteroid.material.fragments()
+            };
+            self.asteroids.extend(asteroid.split(impact, pieces));
+            if asteroid.material == Material::Explosive {
+                self.explode_rock(&asteroid.position, by_player);
+            }
+        }
+    }
+
+    // Each wavefront shoves the ship and every rock it reaches outward, once
+    // per wave. Small rocks it overtakes are destroyed for chain-reaction
+    // points, and larger ones are split too if the setting allows.
+    fn resolve_shock_waves(&mut self) {
+        let push = self.settings.shock_waves.push;
+        let destroy_below = self.settings.shock_waves.destroy_below;
+        let split_large = self.settings.shock_waves.split_large;
+
+        // Waves set off during this pass start next frame
+        for wave_idx in 0..self.shock_waves.len() {
+            let strength = push * self.shock_waves[wave_idx].strength();
+            let (by_player, chain) = (self.shock_waves[wave_idx].by_player, self.shock_waves[wave_idx].chain);
+
+            if !self.ship.hyperspace.is_warping() {
+                if let Some(direction) = self.shock_waves[wave_idx].strike_ship(&self.ship.position, 10.0) {
+                    self.ship.velocity.x += direction.x * strength;
+                    self.ship.velocity.y += direction.y * strength;
+                }
+            }
+
+            // Rocks to destroy are collected first, since an explosive one
+            // can take out its neighbours and shift every index after it
+            let mut struck = Vec::new();
+            for index in 0..self.asteroids.len() {
+                let asteroid = &self.asteroids[index];
+                let Some(direction) = self.shock_waves[wave_idx].strike(asteroid.id, &asteroid.position, asteroid.size)
+                else {
+                    continue;
+                };
+
+                if asteroid.size < destroy_below || split_large {
+                    struck.push((asteroid.id, direction));
+                } else {
+                    // Heavier rocks are pushed less
+                    let impulse = strength * (SHIP_MASS / asteroid.mass()).sqrt();
+                    let asteroid = &mut self.asteroids[index];
+                    asteroid.velocity.x += direction.x * impulse;
+                    asteroid.velocity.y += direction.y * impulse;
+                }
+            }
+
+            for (id, direction) in struck {
+                // Already gone if an earlier rock's explosion reached it
+                let Some(index) = self.asteroids.iter().position(|rock| rock.id == id) else {
+                    continue;
+                };
+                let first_new_id = NEXT_ASTEROID_ID.load(Ordering::Relaxed);
+                let position = self.asteroids[index].position.clone();
+                self.shock_chain = chain;
+                self.destroy_asteroid(index, by_player, &direction);
+                self.shock_chain = 0;
+                if by_player {
+                    self.award_points(CHAIN_BONUS * chain, &position);
+                }
+                // Fragments land inside this wave and shouldn't be hit by it again
+                let fragments: Vec<u32> =
+                    self.asteroids.iter().map(|rock| rock.id).filter(|id| *id >= first_new_id).collect();
+                for id in fragments {
+                    self.shock_waves[wave_idx].exclude(id);
+                }
+            }
+        }
+    }
+
+    fn clear_ordnance(&mut self) {
+        self.missiles.clear();
+        self.mines.clear();
+        self.blasts.clear();
+    }
+
+    // Where the laser starts and which way it points
+    fn laser_ray(&self) -> (Vector, Vector) {
+        let direction = Vector { x: self.ship.rotation.sin(), y: -self.ship.rotation.cos() };
+        let origin = Vector {
+            x: self.ship.position.x + 20.0 * direction.x,
+            y: self.ship.position.y + 20.0 * direction.y,
+        };
+        (origin, direction)
+    }
+
+    // The beam breaks the first rock in its path every few frames
+    fn fire_laser(&mut self) {
+        if !self.ship.weapon.beam_active() {
+            return;
+        }
+        if self.controls_locked() {
+            self.ship.weapon.beam_frames = 0;
+            return;
+        }
+        if self.ship.weapon.laser_hit_timer > 0 {
+            return;
+        }
+
+        let (origin, direction) = self.laser_ray();
+        let rock = ray_cast(&origin, &direction, LASER_RANGE, &self.asteroids);
+        let boss = self.boss.as_ref().and_then(|boss| boss.ray_hit(&origin, &direction, LASER_RANGE));
+        let impact_at = |distance: f64| Vector { x: origin.x + direction.x * distance, y: origin.y + direction.y * distance };
+        match (rock, boss) {
+            // Whichever the beam reaches first takes the hit
+            (_, Some((segment, distance))) if rock.is_none_or(|(_, rock_distance)| distance < rock_distance) => {
+                self.ship.weapon.laser_hit_timer = LASER_HIT_INTERVAL;
+                self.particles.impact_sparks(&impact_at(distance));
+                self.damage_boss(segment, 1);
+            }
+            (Some((index, distance)), _) => {
+                self.ship.weapon.laser_hit_timer = LASER_HIT_INTERVAL;
+                self.particles.impact_sparks(&impact_at(distance));
+                self.play_sound("explosion-sound");
+                self.hit_asteroid(index, true, &direction);
+            }
+            _ => {}
+        }
+    }
+
+    fn resolve_missile_hits(&mut self) {
+        for missile_idx in (0..self.missiles.len()).rev() {
+            let position = &self.missiles[missile_idx].position;
+            let target = self
+                .asteroids
+                .iter()
+                .position(|asteroid| wrap_distance(position, &asteroid.position) < asteroid.size);
+            if let Some(asteroid_idx) = target {
+                let missile = self.missiles.remove(missile_idx);
+                self.particles.impact_sparks(&missile.position);
+                self.play_sound("explosion-sound");
+                self.hit_asteroid(asteroid_idx, true, &missile.velocity);
+            }
+        }
+    }
+
+    // An armed mine goes off when a rock strays close, breaking every rock
+    // (and the saucer) caught in the blast
+    fn detonate_mines(&mut self) {
+        for mine_idx in (0..self.mines.len()).rev() {
+            if !self.asteroids.iter().any(|asteroid| self.mines[mine_idx].triggered_by(asteroid)) {
+                continue;
+            }
+
+            let mine = self.mines.remove(mine_idx);
+            let caught: Vec<u32> = self
+                .asteroids
+                .iter()
+                .filter(|asteroid| wrap_distance(&mine.position, &asteroid.position) < MINE_BLAST_RADIUS + asteroid.size)
+                .map(|asteroid| asteroid.id)
+                .collect();
+            self.destroy_caught(&caught, &mine.position, true);
+
+            let saucer_caught = self.saucer.as_ref().is_some_and(|saucer| {
+                wrap_distance(&mine.position, &saucer.position) < MINE_BLAST_RADIUS + saucer.kind.radius()
+            });
+            if saucer_caught {
+                self.destroy_saucer(true);
+            }
+
+            let boss_caught: Vec<usize> = self.boss.as_ref().map_or(Vec::new(), |boss| {
+                boss.segments()
+                    .into_iter()
+                    .filter(|(_, center, radius)| wrap_distance(&mine.position, center) < MINE_BLAST_RADIUS + radius)
+                    .map(|(segment, _, _)| segment)
+                    .collect()
+            });
+            for segment in boss_caught {
+                self.damage_boss(segment, 2);
+            }
+
+            self.blasts.push(Blast::new(mine.position));
+            self.play_sound("mine-explosion-sound");
+        }
+    }
+
+    fn select(&mut self, kind: WeaponKind) {
+        if self.ship.weapon.current != kind {
+            self.ship.weapon.select(kind);
+            self.emit(GameEvent::WeaponSelected(kind));
+        }
+    }
+
+    fn switch_weapon(&mut self, step: i32) {
+        if !self.state.is_active_play() {
+            return;
+        }
+        let previous = self.ship.weapon.current;
+        self.ship.weapon.cycle(step);
+        let current = self.ship.weapon.current;
+        if current != previous {
+            self.emit(GameEvent::WeaponSelected(current));
+        }
+    }
+
+    // `origin` is where the special rock broke up
+    fn apply_random_disruption(&mut self, origin: &Vector) {
+        if self.ship.power_ups.has(PowerUpKind::Immunity) {
+            return;
+        }
+
+        let disruption = self.settings.disruptions.choose();
+        if disruption == DisruptionType::None {
+            return;
+        }
+
+        match self.disruptions.trigger(disruption, origin) {
+            Trigger::Started => self.disruption_started(disruption),
+            Trigger::Refreshed => {
+                if let Some(sound_id) = disruption.sound_id() {
+                    self.play_sound(sound_id);
+                }
+            }
+            Trigger::Queued => {}
+        }
+    }
+
+    fn disruption_started(&mut self, kind: DisruptionType) {
+        self.emit(GameEvent::DisruptionStarted(kind));
+        if let Some(sound_id) = kind.sound_id() {
+            self.play_sound(sound_id);
+        }
+    }
+
+    // Everything except the ship and its weapons slows down during time dilation
+    fn time_scale(&self) -> f64 {
+        if self.disruptions.has(DisruptionType::TimeDilation) {
+            TIME_DILATION_SCALE
+        } else {
+            1.0
+        }
+    }
+
+    fn collect_power_ups(&mut self) {
+        if !self.state.is_active_play() || self.ship.hyperspace.is_warping() {
+            return;
+        }
+
+        let reach = PICKUP_RADIUS + 10.0;
+        let mut index = 0;
+        while index < self.power_ups.len() {
+            if wrap_distance(&self.power_ups[index].position, &self.ship.position) < reach {
+                let kind = self.power_ups.remove(index).kind;
+                self.apply_power_up(kind);
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    fn apply_power_up(&mut self, kind: PowerUpKind) {
+        match kind {
+            PowerUpKind::ExtraLife => self.grant_lives(1),
+            PowerUpKind::Immunity => {
+                // Also shakes off whatever is currently affecting the ship
+                for ended in self.disruptions.clear() {
+                    self.emit(GameEvent::DisruptionEnded(ended));
+                }
+            }
+            _ => {}
+        }
+        self.ship.power_ups.grant(kind);
+        self.emit(GameEvent::PowerUpCollected(kind));
+        self.play_sound("power-up-sound");
+    }
+
+    fn fire_repeat_frames(&self) -> i32 {
+        if self.ship.power_ups.has(PowerUpKind::RapidFire) {
+            RAPID_FIRE_REPEAT_FRAMES
+        } else {
+            FIRE_REPEAT_FRAMES
+        }
+    }
+
+    // Frames until the next saucer, shrinking as levels go by
+    fn saucer_delay(&self) -> i32 {
+        let base = (1200 - 60 * (self.level - 1)).max(420);
+        base / 2 + (rng::random() * (base / 2) as f64) as i32
+    }
+
+    fn clear_saucers(&mut self) {
+        self.saucer = None;
+        self.enemy_bullets.clear();
+        self.saucer_timer = self.saucer_delay();
+    }
+
+    fn update_saucer(&mut self) {
+        let time_scale = self.time_scale();
+        let saucer = match self.saucer.as_mut() {
+            Some(saucer) => saucer,
+            // Saucers stay away while a boss is on the field
+            None if self.boss.is_some() => return,
+            None => {
+                self.saucer_timer -= 1;
+                if self.saucer_timer <= 0 {
+                    let kind = SaucerKind::choose(self.level, self.score);
+                    self.saucer = Some(Saucer::new(kind));
+                    self.emit(GameEvent::SaucerSpawned(kind));
+                }
+                return;
+            }
+        };
+
+        if !saucer.update(time_scale) {
+            // Flew off the far edge
+            self.saucer = None;
+            self.saucer_timer = self.saucer_delay();
+            return;
+        }
+
+        let kind = saucer.kind;
+        let hum = saucer.ready_to_hum();
+        if saucer.ready_to_fire() {
+            let angle = saucer.firing_angle(&self.ship.position, self.level, self.score);
+            self.enemy_bullets.push(Bullet::enemy(&saucer.position, angle, kind.radius() + 4.0));
+            self.play_sound("enemy-shoot-sound");
+        }
+        if hum {
+            self.play_sound(kind.sound_id());
+        }
+    }
+
+    // Moves the boss and carries out each attack as it comes due
+    fn update_boss(&mut self) {
+        let time_scale = self.time_scale();
+        let boss = match self.boss.as_mut() {
+            Some(boss) => boss,
+            None => return,
+        };
+
+        let attack = boss.update(time_scale);
+        if boss.pulling() && !self.ship.hyperspace.is_warping() {
+            let pull = boss.pull(&self.ship.position);
+            self.ship.velocity.x += pull.x;
+            self.ship.velocity.y += pull.y;
+        }
+        if let Some(attack) = attack {
+            self.boss_attack(attack);
+        }
+    }
+
+    fn boss_attack(&mut self, attack: BossAttack) {
+        let (position, reach) = match &self.boss {
+            Some(boss) => (boss.position.clone(), boss.reach()),
+            None => return,
+        };
+
+        match attack {
+            BossAttack::SpawnRocks => {
+                let heading = rng::random() * 2.0 * PI;
+                for side in [0.0, PI] {
+                    let (sin, cos) = (heading + side).sin_cos();
+                    let mut rock = Asteroid::new(
+                        (position.x + cos * (reach + 20.0)).rem_euclid(WORLD_WIDTH),
+                        (position.y + sin * (reach + 20.0)).rem_euclid(WORLD_HEIGHT),
+                        20.0,
+                    );
+                    rock.velocity = Vector { x: cos * 2.0, y: sin * 2.0 };
+                    self.asteroids.push(rock);
+                }
+                self.play_sound("explosion-sound");
+            }
+            BossAttack::Volley => {
+                for shot in 0..12 {
+                    let angle = shot as f64 * PI / 6.0;
+                    self.enemy_bullets.push(Bullet::enemy(&position, angle, reach + 4.0));
+                }
+                self.play_sound("enemy-shoot-sound");
+            }
+            BossAttack::AimedBurst => {
+                let delta = wrap_delta(&position, &self.ship.position);
+                let aim = delta.x.atan2(-delta.y);
+                for spread in [-0.15, 0.0, 0.15] {
+                    self.enemy_bullets.push(Bullet::enemy(&position, aim + spread, reach + 4.0));
+                }
+                self.play_sound("enemy-shoot-sound");
+            }
+            BossAttack::GravityPull => {
+                if let Some(boss) = self.boss.as_mut() {
+                    boss.start_pull();
+                }
+                self.play_sound("gravity-pulse-sound");
+            }
+        }
+    }
+
+    // Player fire chips segments off the boss, missiles hitting harder, and
+    // touching it is fatal without the shield. Returns true if the ship was
+    // destroyed.
+    fn resolve_boss_hits(&mut self) -> bool {
+        let boss = match &self.boss {
+            Some(boss) => boss,
+            None => return false,
+        };
+
+        let ship_contact = if self.ship_vulnerable() {
+            let reach = if self.ship.shield.active { SHIELD_RADIUS } else { 10.0 };
+            boss.hit_test(&self.ship.position, reach).map(|segment| {
+                let (_, center, radius) = boss.segments().into_iter().find(|(index, _, _)| *index == segment).unwrap();
+                (center, radius + reach)
+            })
+        } else {
+            None
+        };
+        let bullet_hits: Vec<(usize, usize)> = self
+            .bullets
+            .iter()
+            .enumerate()
+            .filter(|(_, bullet)| bullet.pierce_cooldown == 0)
+            .filter_map(|(bullet_idx, bullet)| boss.hit_test(&bullet.position, 0.0).map(|segment| (bullet_idx, segment)))
+            .collect();
+        let missile_hits: Vec<(usize, usize)> = self
+            .missiles
+            .iter()
+            .enumerate()
+            .filter_map(|(missile_idx, missile)| boss.hit_test(&missile.position, 0.0).map(|segment| (missile_idx, segment)))
+            .collect();
+
+        if let Some((center, contact)) = ship_contact {
+            if !self.ship.shield.active {
+                self.destroy_ship();
+                return true;
+            }
+            // The shield bounces the ship off the hull
+            let delta = wrap_delta(&center, &self.ship.position);
+            let distance = (delta.x * delta.x + delta.y * delta.y).sqrt().max(0.001);
+            let normal = Vector { x: delta.x / distance, y: delta.y / distance };
+            self.ship.position.x = (center.x + normal.x * contact).rem_euclid(WORLD_WIDTH);
+            self.ship.position.y = (center.y + normal.y * contact).rem_euclid(WORLD_HEIGHT);
+            let closing = self.ship.velocity.x * normal.x + self.ship.velocity.y * normal.y;
+            if closing < 0.0 {
+                self.ship.velocity.x -= 2.0 * closing * normal.x;
+                self.ship.velocity.y -= 2.0 * closing * normal.y;
+            }
+            self.shield_impact();
+        }
+
+        // Remove in reverse order to keep the remaining indices valid
+        for (bullet_idx, segment) in bullet_hits.into_iter().rev() {
+            self.particles.impact_sparks(&self.bullets[bullet_idx].position);
+            self.bullets[bullet_idx].scored = true;
+            if self.bullets[bullet_idx].piercing {
+                self.bullets[bullet_idx].pierce_cooldown = PIERCE_COOLDOWN_FRAMES;
+            } else {
+                self.bullets.remove(bullet_idx);
+            }
+            self.damage_boss(segment, 1);
+        }
+        for (missile_idx, segment) in missile_hits.into_iter().rev() {
+            let missile = self.missiles.remove(missile_idx);
+            self.particles.impact_sparks(&missile.position);
+            self.play_sound("explosion-sound");
+            self.damage_boss(segment, 2);
+        }
+        false
+    }
+
+    fn damage_boss(&mut self, segment: usize, hits: i32) {
+        let boss = match self.boss.as_mut() {
+            Some(boss) => boss,
+            None => return,
+        };
+        let broken = boss.damage(segment, hits);
+        let phase_changed = boss.advance_phase();
+        let (phase, defeated, segment_score) = (boss.phase, boss.is_defeated(), boss.definition.segment_score);
+        self.register_hit();
+
+        match broken {
+            Some((center, radius)) => {
+                self.particles.asteroid_explosion(&center, radius * 2.0);
+                self.award_points(segment_score, &center);
+                self.play_sound("explosion-sound");
+            }
+            None => self.play_sound("metal-hit-sound"),
+        }
+
+        if defeated {
+            self.defeat_boss();
+        } else if phase_changed {
+            self.emit(GameEvent::BossPhase(phase + 1));
+            self.play_sound("boss-phase-sound");
+        }
+    }
+
+    fn defeat_boss(&mut self) {
+        if let Some(boss) = self.boss.take() {
+            self.particles.asteroid_explosion(&boss.position, boss.reach() * 2.0);
+            self.particles.electric_burst(&boss.position, &self.settings.accessibility.theme());
+            self.award_points(boss.definition.score, &boss.position);
+            self.emit(GameEvent::BossDefeated(boss.definition.name));
+            self.play_sound("boss-defeated-sound");
+        }
+    }
+
+    fn destroy_saucer(&mut self, by_player: bool) {
+        if let Some(saucer) = self.saucer.take() {
+            if by_player {
+                self.register_hit();
+                self.award_points(saucer.kind.score(), &saucer.position);
+            }
+            self.particles.asteroid_explosion(&saucer.position, saucer.kind.radius() * 2.0);
+            self.play_sound("explosion-sound");
+            self.emit(GameEvent::SaucerDestroyed(saucer.kind));
+            self.saucer_timer = self.saucer_delay();
+        }
+    }
+
+    fn ship_vulnerable(&self) -> bool {
+        self.state.can_lose_ship() && !self.ship.hyperspace.is_warping()
+    }
+
+    // Returns true if the ship was destroyed
+    fn resolve_saucer_hits(&mut self) -> bool {
+        let (position, radius) = match &self.saucer {
+            Some(saucer) => (saucer.position.clone(), saucer.kind.radius()),
+            None => return false,
+        };
+
+        if let Some(bullet_idx) = self.bullets.iter().position(|bullet| wrap_distance(&bullet.position, &position) < radius) {
+            let bullet = self.bullets.remove(bullet_idx);
+            self.particles.impact_sparks(&bullet.position);
+            self.destroy_saucer(true);
+            return false;
+        }
+
+        let rock = self
+            .asteroids
+            .iter()
+            .position(|asteroid| wrap_distance(&asteroid.position, &position) < asteroid.size + radius);
+        if let Some(asteroid_idx) = rock {
+            self.destroy_saucer(false);
+            let impact = wrap_delta(&position, &self.asteroids[asteroid_idx].position);
+            self.destroy_asteroid(asteroid_idx, false, &impact);
+            return false;
+        }
+
+        if self.ship_vulnerable() {
+            let reach = if self.ship.shield.active { SHIELD_RADIUS } else { 10.0 };
+            if wrap_distance(&self.ship.position, &position) < radius + reach {
+                self.destroy_saucer(true);
+                if self.ship.shield.active {
+                    self.shield_impact();
+                } else {
+                    self.destroy_ship();
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    // Saucer fire breaks rocks (without scoring) and kills an unshielded
+    // ship. Returns true if the ship was destroyed.
+    fn resolve_enemy_bullet_hits(&mut self) -> bool {
+        for bullet_idx in (0..self.enemy_bullets.len()).rev() {
+            let position = self.enemy_bullets[bullet_idx].position.clone();
+
+            let rock = self
+                .asteroids
+                .iter()
+                .position(|asteroid| wrap_distance(&position, &asteroid.position) < asteroid.size);
+            if let Some(asteroid_idx) = rock {
+                let bullet = self.enemy_bullets.remove(bullet_idx);
+                self.hit_asteroid(asteroid_idx, false, &bullet.velocity);
+                continue;
+            }
+
+            if self.ship_vulnerable() {
+                let reach = if self.ship.shield.active { SHIELD_RADIUS } else { 10.0 };
+                if wrap_distance(&position, &self.ship.position) < reach {
+                    self.enemy_bullets.remove(bullet_idx);
+                    if self.ship.shield.active {
+                        self.shield_impact();
+                    } else {
+                        self.destroy_ship();
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    // With self-hits on, the player's own bullets are as deadly as saucer
+    // fire once clear of the muzzle. Returns true if the ship was destroyed.
+    fn resolve_self_hits(&mut self) -> bool {
+        if !self.settings.bullets.self_hit || !self.ship_vulnerable() {
+            return false;
+        }
+
+        let reach = if self.ship.shield.active { SHIELD_RADIUS } else { 10.0 };
+        let hit = self.bullets.iter().position(|bullet| {
+            bullet.travelled > SELF_HIT_ARMING_DISTANCE && wrap_distance(&bullet.position, &self.ship.position) < reach
+        });
+        let bullet_idx = match hit {
+            Some(bullet_idx) => bullet_idx,
+            None => return false,
+        };
+
+        self.bullets.remove(bullet_idx);
+        if self.ship.shield.active {
+            self.shield_impact();
+            return false;
+        }
+        self.destroy_ship();
+        true
+    }
+
+    fn shield_impact(&mut self) {
+        self.emit(GameEvent::ShieldHit);
+        self.play_sound("shield-hit-sound");
+        if self.ship.shield.absorb_impact() {
+            self.emit(GameEvent::ShieldDepleted);
+        }
+    }
+
+    // Bounce the shielded ship and any rock touching the bubble apart,
+    // exchanging momentum by mass; each new impact costs shield energy
+    fn deflect_asteroids(&mut self) {
+        let speed_multiplier = self.get_speed_multiplier();
+        let mut impacts = 0;
+
+        for asteroid in &mut self.asteroids {
+            let delta = wrap_delta(&asteroid.position, &self.ship.position);
+            let distance = (delta.x * delta.x + delta.y * delta.y).sqrt();
+            let reach = asteroid.size + SHIELD_RADIUS;
+            if distance >= reach || distance == 0.0 {
+                continue;
+            }
+
+            let normal = Vector { x: delta.x / distance, y: delta.y / distance };
+            let rock_mass = asteroid.mass();
+            let total_mass = SHIP_MASS + rock_mass;
+
+            // Push the pair out of contact, the lighter body moving further
+            let overlap = reach - distance;
+            self.ship.position.x += normal.x * overlap * rock_mass / total_mass;
+            self.ship.position.y += normal.y * overlap * rock_mass / total_mass;
+            asteroid.position.x -= normal.x * overlap * SHIP_MASS / total_mass;
+            asteroid.position.y -= normal.y * overlap * SHIP_MASS / total_mass;
+
+            // Rocks move at velocity times the level speed
+            let mut rock_velocity = Vector {
+                x: asteroid.velocity.x * speed_multiplier,
+                y: asteroid.velocity.y * speed_multiplier,
+            };
+            let closing = (self.ship.velocity.x - rock_velocity.x) * normal.x
+                + (self.ship.velocity.y - rock_velocity.y) * normal.y;
+            if closing >= 0.0 {
+                continue; // Already separating
+            }
+
+            let impulse = -(1.0 + SHIELD_RESTITUTION) * closing / (1.0 / SHIP_MASS + 1.0 / rock_mass);
+            self.ship.velocity.x += normal.x * impulse / SHIP_MASS;
+            self.ship.velocity.y += normal.y * impulse / SHIP_MASS;
+            rock_velocity.x -= normal.x * impulse / rock_mass;
+            rock_velocity.y -= normal.y * impulse / rock_mass;
+            asteroid.velocity = Vector { x: rock_velocity.x / speed_multiplier, y: rock_velocity.y / speed_multiplier };
+            impacts += 1;
+        }
+
+        for _ in 0..impacts {
+            if self.ship.shield.active {
+                self.shield_impact();
+            }
+        }
+    }
+
+    fn destroy_ship(&mut self) {
+        self.particles.ship_debris(&self.ship.position, self.ship.rotation, &self.ship.velocity);
+        self.lives -= 1;
+        self.break_combo();
+        self.near_misses.clear();
+        self.play_sound("explosion-sound");
+
+        if self.lives <= 0 {
+            self.transition(GameState::GameOver);
+        } else {
+            self.transition(GameState::Respawning);
+        }
+    }
+
+    // End of the departure animation: either the drive fails and takes the
+    // ship with it, or the ship reappears elsewhere. Returns false if the ship
+    // was lost.
+    fn complete_jump(&mut self) -> bool {
+        if rng::random() < self.settings.hyperspace.malfunction_chance {
+            self.emit(GameEvent::HyperspaceMalfunction);
+            self.destroy_ship();
+            return false;
+        }
+
+        self.ship.position = choose_destination(&self.asteroids, self.settings.hyperspace.safe_landing);
+        // Never land inside a gravity well's grip
+        for _ in 0..10 {
+            if self.gravity_wells.iter().all(|well| well.keep_clear(&self.ship.position, 60.0)) {
+                break;
+            }
+            self.ship.position = choose_destination(&self.asteroids, self.settings.hyperspace.safe_landing);
+        }
+        self.ship.velocity = Vector { x: 0.0, y: 0.0 };
+        self.ship.hyperspace.arrive();
+        true
+    }
+
+    // Update asteroids with speed multiplier
+    fn move_asteroids(&mut self) {
+        let speed_multiplier = self.get_speed_multiplier() * self.time_scale();
+        for asteroid in &mut self.asteroids {
+            asteroid.update(speed_multiplier);
+        }
+    }
+
+    // Player controls only respond while flying and not disabled
+    fn controls_locked(&self) -> bool {
+        !self.state.is_active_play()
+            || self.disruptions.has(DisruptionType::Disabled)
+            || self.ship.hyperspace.is_warping()
+    }
+
+    fn release_inputs(&mut self) {
+        self.held_keys.clear();
+        self.shield_held = false;
+        self.touch.clear();
+        self.mouse.clear();
+    }
+
+    fn apply_keyboard_input(&mut self) {
+        if self.held_keys.contains("ArrowLeft") {
+            self.rotate(-0.1);
+        }
+        if self.held_keys.contains("ArrowRight") {
+            self.rotate(0.1);
+        }
+        if self.held_keys.contains("ArrowUp") {
+            self.thrust();
+        }
+        if self.held_keys.contains("s") || self.held_keys.contains("S") {
+            self.ship.shield.requested = true;
+        }
+        if self.ship.power_ups.rapid_fire_ready(self.held_keys.contains(" ")) {
+            self.shoot();
+        } else if self.ship.weapon.current == WeaponKind::Laser && self.held_keys.contains(" ") {
+            // Keep the beam on for as long as Space is held
+            self.shoot();
+        }
+    }
+
+    fn apply_gamepad_input(&mut self) {
+        let frame = match self.gamepad.poll() {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        if self.menu_visible() {
+            self.apply_gamepad_menu_input(&frame);
+            return;
+        }
+
+        if frame.pressed(PAD_START) {
+            self.pause();
+            return;
+        }
+
+        // Analog stick when deflected, otherwise the d-pad
+        let rotate = if frame.stick.x != 0.0 {
+            frame.stick.x * 0.1
+        } else if frame.held(PAD_LEFT) {
+            -0.1
+        } else if frame.held(PAD_RIGHT) {
+            0.1
+        } else {
+            0.0
+        };
+        if rotate != 0.0 {
+            self.rotate(rotate);
+        }
+        if frame.held(PAD_RIGHT_TRIGGER) || frame.held(PAD_UP) {
+            self.thrust();
+        }
+        let repeat_frames = self.fire_repeat_frames();
+        if self.gamepad.fire(&frame, repeat_frames) {
+            self.shoot();
+        }
+        // Not d-pad down: the left stick also drives it, and a teleport is too
+        // risky to trigger by leaning on the stick
+        if frame.pressed(PAD_Y) {
+            self.hyperspace();
+        }
+        if frame.held(PAD_LEFT_BUMPER) {
+            self.ship.shield.requested = true;
+        }
+        if frame.pressed(PAD_RIGHT_BUMPER) {
+            self.next_weapon();
+        }
+    }
+
+    fn apply_gamepad_menu_input(&mut self, frame: &GamepadFrame) {
+        if frame.pressed(PAD_START) {
+            self.menu_input(if self.state == GameState::Paused { MenuInput::Back } else { MenuInput::Select });
+            return;
+        }
+
+        let mapping = [
+            (PAD_UP, MenuInput::Up),
+            (PAD_DOWN, MenuInput::Down),
+            (PAD_LEFT, MenuInput::Left),
+            (PAD_RIGHT, MenuInput::Right),
+            (PAD_A, MenuInput::Select),
+            (PAD_B, MenuInput::Back),
+        ];
+        for (button, input) in mapping {
+            if frame.pressed(button) {
+                self.menu_input(input);
+            }
+        }
+    }
+
+    fn apply_touch_input(&mut self) {
+        if !self.settings.control_scheme.uses_touch() {
+            return;
+        }
+
+        let input = self.touch.sample(self.fire_repeat_frames());
+        if input.rotate != 0.0 {
+            self.rotate(input.rotate);
+        }
+        if let Some(target) = input.aim {
+            // Tap-to-aim: turn toward the finger and thrust while it is held
+            self.turn_towards(&target, 0.1);
+            self.thrust();
+        }
+        if input.thrust {
+            self.thrust();
+        }
+        if input.fire {
+            self.shoot();
+        }
+    }
+
+    fn apply_mouse_input(&mut self) {
+        if self.settings.control_scheme != ControlScheme::MouseAim {
+            return;
+        }
+
+        if let Some(target) = self.mouse.target.clone() {
+            self.turn_towards(&target, self.mouse.turn_rate);
+        }
+        let (fire, thrust) = self.mouse.sample(self.fire_repeat_frames());
+        if thrust {
+            self.thrust();
+        }
+        if fire {
+            self.shoot();
+        }
+    }
+
+    fn turn_towards(&mut self, target: &Vector, max_turn: f64) {
+        if self.controls_locked() {
+            return;
+        }
+
+        // The target is in screen space, which is flipped in mirror world
+        let target = if self.disruptions.has(DisruptionType::MirrorWorld) {
+            Vector { x: WORLD_WIDTH - target.x, y: target.y }
+        } else {
+            target.clone()
+        };
+
+        // Aim at whichever wrapped image of the target is closest
+        let mut delta = wrap_delta(&self.ship.position, &target);
+        if self.disruptions.has(DisruptionType::Scrambled) {
+            delta.x = -delta.x; // Mirror the aim target across the ship
+        }
+
+        let desired = delta.x.atan2(-delta.y); // Rotation 0 points up the screen
+        let difference = (desired - self.ship.rotation + PI).rem_euclid(2.0 * PI) - PI;
+        self.ship.rotation += difference.clamp(-max_turn, max_turn);
+    }
+
+    // Map page (client) coordinates to world coordinates, undoing CSS size,
+    // transforms and the canvas border
+    fn client_to_world(&self, client_x: f64, client_y: f64) -> Vector {
+        let canvas = match self.ctx.canvas() {
+            Some(canvas) => canvas,
+            None => return Vector { x: client_x, y: client_y },
+        };
+
+        let rect = canvas.get_bounding_client_rect();
+        let layout_width = canvas.offset_width() as f64;
+        let layout_height = canvas.offset_height() as f64;
+        let transform_x = if layout_width > 0.0 { rect.width() / layout_width } else { 1.0 };
+        let transform_y = if layout_height > 0.0 { rect.height() / layout_height } else { 1.0 };
+
+        let local_x = (client_x - rect.left()) / transform_x - canvas.client_left() as f64;
+        let local_y = (client_y - rect.top()) / transform_y - canvas.client_top() as f64;
+        let content_width = (canvas.client_width() as f64).max(1.0);
+        let content_height = (canvas.client_height() as f64).max(1.0);
+
+        Vector {
+            x: local_x * WORLD_WIDTH / content_width,
+            y: local_y * WORLD_HEIGHT / content_height,
+        }
+    }
+
+    fn menu_visible(&self) -> bool {
+        matches!(self.state, GameState::Title | GameState::Paused | GameState::GameOver)
+    }
+
+    fn menu_input(&mut self, input: MenuInput) {
+        let items = self.menu.items(&self.settings, &self.i18n);
+        let selected = items.get(self.menu.selected).map(|item| item.action);
+
+        match input {
+            MenuInput::Up | MenuInput::Down => {
+                let step = if input == MenuInput::Up { -1 } else { 1 };
+                self.menu.move_selection(step, items.len());
+                self.play_sound("menu-move-sound");
+            }
+            MenuInput::Left | MenuInput::Right => {
+                if let Some(MenuAction::Adjust(key)) = selected {
+                    let step = if input == MenuInput::Left { -1 } else { 1 };
+                    self.adjust_setting(key, step);
+                    self.play_sound("menu-move-sound");
+                }
+            }
+            MenuInput::Select => {
+                if let Some(action) = selected {
+                    self.play_sound("menu-select-sound");
+                    self.activate_menu_action(action);
+                }
+            }
+            MenuInput::Back => self.menu_back(),
+        }
+    }
+
+    fn menu_back(&mut self) {
+        // Backing out of the pause menu itself resumes play
+        if !self.menu.pop() && self.state == GameState::Paused {
+            self.resume();
+        }
+    }
+
+    fn activate_menu_action(&mut self, action: MenuAction) {
+        match action {
+            MenuAction::StartGame => self.reset(),
+            MenuAction::Resume => self.resume(),
+            MenuAction::QuitToTitle => {
+                self.transition(GameState::Title);
+            }
+            MenuAction::Open(page) => self.menu.push(page),
+            MenuAction::Back => self.menu_back(),
+            MenuAction::SaveScore => self.save_high_score(),
+            MenuAction::Adjust(key) => self.adjust_setting(key, 1),
+        }
+    }
+
+    fn adjust_setting(&mut self, key: SettingKey, step: i32) {
+        match key {
+            SettingKey::Volume => {
+                let tenths = (self.settings.volume * 10.0).round() as i32 + step;
+                self.settings.volume = tenths.clamp(0, 10) as f64 / 10.0;
+            }
+            SettingKey::Difficulty => {
+                self.settings.difficulty = self.settings.difficulty.cycled(step);
+            }
+            SettingKey::ControlScheme => {
+                let scheme = self.settings.control_scheme.cycled(step);
+                self.apply_control_scheme(scheme);
+            }
+            SettingKey::Language => {
+                let locales = self.i18n.available();
+                let options: Vec<&str> = locales.iter().map(String::as_str).collect();
+                let next = cycle(&options, self.i18n.locale(), step).to_string();
+                self.i18n.set_locale(&next);
+            }
+            SettingKey::Particles => {
+                self.settings.particles = self.settings.particles.cycled(step);
+                self.particles.set_quality(self.settings.particles);
+            }
+            SettingKey::ReducedFlash => {
+                self.settings.accessibility.reduced_flash = !self.settings.accessibility.reduced_flash;
+            }
+            SettingKey::Palette => {
+                self.settings.accessibility.palette = self.settings.accessibility.palette.cycled(step);
+            }
+            SettingKey::HighContrast => {
+                self.settings.accessibility.high_contrast = !self.settings.accessibility.high_contrast;
+            }
+            SettingKey::HudScale => {
+                let scale = self.settings.accessibility.hud_scale + 0.25 * step as f64;
+                self.settings.accessibility.hud_scale = scale.clamp(HUD_SCALE_MIN, HUD_SCALE_MAX);
+            }
+            SettingKey::HazardMarkers => {
+                self.settings.accessibility.hazard_markers = !self.settings.accessibility.hazard_markers;
+            }
+        }
+    }
+
+    fn apply_control_scheme(&mut self, scheme: ControlScheme) {
+        self.settings.control_scheme = scheme;
+        self.release_inputs();
+    }
+
+    // Pick the menu item under a pointer and activate it
+    fn menu_click(&mut self, point: &Vector) {
+        let item_count = self.menu.items(&self.settings, &self.i18n).len();
+        if let Some(index) = self.menu.item_at(point, item_count, self.menu_lines().len()) {
+            self.menu.selected = index;
+            self.menu_input(MenuInput::Select);
+        }
+    }
+
+    fn save_high_score(&mut self) {
+        let name = clean_name(&self.player_name);
+        let entry = HighScoreEntry {
+            name: if name.is_empty() { self.i18n.text("name-entry-default") } else { name },
+            score: self.score,
+            level: self.level,
+            difficulty: self.settings.difficulty,
+            date: js_sys::Date::new_0().to_iso_string().as_string().unwrap_or_default().chars().take(10).collect(),
+            seed: self.seed,
+        };
+        if let Some(rank) = self.high_scores.insert(entry) {
+            self.emit(GameEvent::HighScore(rank + 1));
+        }
+        self.menu.open_root(MenuPage::GameOver);
+    }
+
+    // Typing on the name entry page; returns true if the key was used
+    fn type_name(&mut self, key: &str) -> bool {
+        if key == "Backspace" {
+            self.player_name.pop();
+            return true;
+        }
+        let mut chars = key.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if !c.is_control() => {
+                if self.player_name.chars().count() < MAX_NAME_LENGTH {
+                    self.player_name.push(c);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Text shown above the items on the current menu page
+    fn menu_lines(&self) -> Vec<String> {
+        let i18n = &self.i18n;
+        match self.menu.page() {
+            MenuPage::Main => vec![i18n.text("title-controls-hint")],
+            MenuPage::GameOver => vec![
+                i18n.format("final-score", &[("score", self.score as i64)]),
+                i18n.format("level-reached", &[("level", self.level as i64)]),
+            ],
+            MenuPage::NameEntry => {
+                let cursor = if self.player_name.chars().count() < MAX_NAME_LENGTH { "_" } else { "" };
+                vec![
+                    i18n.format("final-score", &[("score", self.score as i64)]),
+                    i18n.text("name-entry-prompt"),
+                    format!("{}{}", self.player_name, cursor),
+                ]
+            }
+            MenuPage::HighScores => {
+                let mut lines: Vec<String> = self
+                    .high_scores
+                    .entries()
+                    .iter()
+                    .enumerate()
+                    .map(|(rank, entry)| {
+                        let place = i18n.format(
+                            "high-scores-entry",
+                            &[
+                                ("rank", rank as i64 + 1),
+                                ("score", entry.score as i64),
+                                ("level", entry.level as i64),
+                            ],
+                        );
+                        format!("{}   {}   {}   {}", place, entry.name, i18n.text(entry.difficulty.label_key()), entry.date)
+                    })
+                    .collect();
+                if lines.is_empty() {
+                    lines.push(i18n.text("high-scores-empty"));
+                }
+                if self.high_scores.rejected > 0 {
+                    lines.push(i18n.plural("high-scores-rejected", self.high_scores.rejected as i64, &[]));
+                }
+                lines
+            }
+            MenuPage::Credits => vec![
+                i18n.text("credits-inspiration"),
+                i18n.text("credits-language"),
+                i18n.text("credits-audio"),
+                i18n.text("credits-graphics"),
+            ],
+            MenuPage::Pause | MenuPage::Settings | MenuPage::Accessibility => Vec::new(),
+        }
+    }
+
+    fn render_menu(&self, theme: &Theme) {
+        let items = self.menu.items(&self.settings, &self.i18n);
+        self.menu.draw(&self.ctx, &items, &self.menu_lines(), theme, &self.i18n);
+    }
+
+    fn play_sound(&self, sound_id: &str) {
+        if self.settings.volume <= 0.0 {
+            return;
+        }
+        let mut audio = self.audio.borrow_mut();
+        if audio.is_none() {
+            *audio = web_sys::AudioContext::new().ok();
+        }
+        if let Some(audio_context) = audio.as_ref() {
+            // A context made before the first key press or click starts suspended
+            let _ = audio_context.resume();
+            play_sound(audio_context, sound_id, self.settings.volume);
+        }
+    }
+
+    fn hud_font(&self, theme: &Theme, size: f64) -> String {
+        format!("{}px Arial", (size * theme.hud_scale).round())
+    }
+
+    // HUD text gets a dark outline in high contrast mode so it reads over rocks
+    fn hud_text(&self, theme: &Theme, text: &str, x: f64, y: f64) {
+        if theme.high_contrast {
+            self.ctx.save();
+            self.ctx.set_stroke_style_str("black");
+            self.ctx.set_line_width(4.0);
+            self.ctx.stroke_text(text, x, y).unwrap();
+            self.ctx.restore();
+        }
+        self.ctx.fill_text(text, x, y).unwrap();
+    }
+
+    pub fn render(&self) {
+        let theme = self.settings.accessibility.theme();
+        self.ctx.clear_rect(0.0, 0.0, 800.0, 600.0);
+        self.ctx.set_stroke_style_str("white");
+        self.ctx.set_fill_style_str("white");
+        self.ctx.set_line_width(theme.line_scale);
+
+        // Canvas text direction isn't exposed by web-sys yet
+        let rtl = self.i18n.is_rtl();
+        let direction = JsValue::from_str(if rtl { "rtl" } else { "ltr" });
+        let _ = Reflect::set(&self.ctx, &JsValue::from_str("direction"), &direction);
+
+        if self.state == GameState::Title {
+            self.render_title(&theme);
+            return;
+        }
+
+        // While paused, draw the suspended scene underneath the overlay
+        let scene = if self.state == GameState::Paused { self.paused_from } else { self.state };
+        let line_height = 30.0 * theme.hud_scale;
+        
+        // Score and level sit on the leading edge, lives on the trailing edge;
+        // right-to-left locales mirror the layout
+        let (leading_x, leading_align, trailing_x, trailing_align) =
+            if rtl { (780.0, "right", 20.0, "left") } else { (20.0, "left", 780.0, "right") };
+
+        self.ctx.set_font(&self.hud_font(&theme, 24.0));
+        self.ctx.set_text_align(leading_align);
+        let score = self.i18n.format("hud-score", &[("score", self.score as i64)]);
+        let level = self.i18n.format("hud-level", &[("level", self.level as i64)]);
+        self.hud_text(&theme, &score, leading_x, 40.0);
+        self.hud_text(&theme, &level, leading_x, 40.0 + line_height);
+        self.render_combo(&theme, leading_x, rtl, 40.0 + 2.0 * line_height);
+        
+        self.ctx.set_text_align(trailing_align);
+        // A new life lights up the counter, blinking unless reduced flash holds it steady
+        let lit = self.extra_life_flash > 0 && (theme.reduced_flash || self.extra_life_flash % 20 > 10);
+        self.ctx.save();
+        self.ctx.set_fill_style_str(if lit { theme.highlight } else { "white" });
+        self.hud_text(&theme, &self.i18n.plural("hud-lives", self.lives as i64, &[]), trailing_x, 40.0);
+        self.ctx.restore();
+        self.hud_text(&theme, &self.i18n.text("hud-shield"), trailing_x, 40.0 + line_height);
+
+        // Shield energy meter below its label, filling from the trailing edge
+        let meter_width = 100.0 * theme.hud_scale;
+        let meter_height = 6.0 * theme.hud_scale;
+        let meter_x = if rtl { trailing_x } else { trailing_x - meter_width };
+        let meter_y = 40.0 + line_height + 8.0 * theme.hud_scale;
+        let charge = self.ship.shield.energy / MAX_ENERGY;
+        self.ctx.save();
+        self.ctx.set_stroke_style_str("white");
+        self.ctx.set_fill_style_str(if self.ship.shield.active { theme.highlight } else { "white" });
+        self.ctx.stroke_rect(meter_x, meter_y, meter_width, meter_height);
+        let fill_x = if rtl { meter_x } else { meter_x + meter_width * (1.0 - charge) };
+        self.ctx.fill_rect(fill_x, meter_y, meter_width * charge, meter_height);
+        self.ctx.restore();
+
+        self.render_weapon_status(&theme, trailing_x, rtl, meter_y + meter_height + 8.0 * theme.hud_scale);
+
+        self.render_power_up_timers(&theme, leading_x, leading_align);
+        self.render_current_weapon(&theme, trailing_x, trailing_align);
+
+        self.render_disruptions(&theme, 40.0 + 2.0 * line_height);
+        self.render_boss_health(&theme);
+
+        if self.state.is_active_play() {
+            if self.settings.control_scheme.uses_touch() {
+                self.touch.draw(&self.ctx, self.settings.control_scheme, &self.i18n);
+            }
+            if self.settings.control_scheme == ControlScheme::MouseAim {
+                self.mouse.draw(&self.ctx);
+            }
+        }
+        
+        // Draw level transition countdown
+        if scene == GameState::LevelTransition {
+            self.ctx.set_font(&self.hud_font(&theme, 48.0));
+            self.ctx.set_text_align("center");
+            let banner = self.i18n.format("level-banner", &[("level", self.level as i64)]);
+            self.hud_text(&theme, &banner, 400.0, 280.0);
+            self.ctx.set_font(&self.hud_font(&theme, 24.0));
+            let countdown = (self.state_timer / 60) + 1;
+            let countdown_text = self.i18n.plural("level-countdown", countdown as i64, &[]);
+            self.hud_text(&theme, &countdown_text, 400.0, 280.0 + 40.0 * theme.hud_scale);
+            self.hud_text(&theme, &self.i18n.text("level-get-ready"), 400.0, 280.0 + 70.0 * theme.hud_scale);
+            self.render_pause_overlay(&theme);
+            return;
+        }
+        
+        // Mirror world flips the playfield (but not the HUD), and a blackout
+        // hides everything outside a circle around the ship
+        self.ctx.save();
+        if self.disruptions.has(DisruptionType::MirrorWorld) {
+            self.ctx.translate(WORLD_WIDTH, 0.0).unwrap();
+            self.ctx.scale(-1.0, 1.0).unwrap();
+        }
+        for effect in &self.disruptions.active {
+            match effect.kind {
+                DisruptionType::GravityPulse => {
+                    draw_gravity_pulse(&self.ctx, &self.disruptions.gravity_origin, effect.remaining, &theme);
+                }
+                DisruptionType::MagneticField => {
+                    draw_magnetic_field(&self.ctx, effect.remaining, self.disruptions.magnetic_direction, &theme);
+                }
+                DisruptionType::TimeDilation => draw_time_dilation(&self.ctx, &theme),
+                _ => {}
+            }
+        }
+        // Clip last so the other effects' visuals stay on screen
+        if self.disruptions.has(DisruptionType::Blackout) {
+            draw_light_edge(&self.ctx, &self.ship.position, &theme);
+            clip_to_light(&self.ctx, &self.ship.position);
+        }
+
+        // Make ship blink during respawn period, or dim it steadily with reduced flash
+        if scene == GameState::Playing {
+            self.ship.draw(&self.ctx);
+            self.ship.shield.draw(&self.ctx, &self.ship.position, &theme);
+            self.ship.hyperspace.draw(&self.ctx, &self.ship.position, theme.highlight);
+            self.disruptions.draw_cues(&self.ctx, &self.ship.position, &theme);
+        } else if scene == GameState::Respawning {
+            if theme.reduced_flash {
+                self.ctx.save();
+                self.ctx.set_global_alpha(0.4);
+                self.ship.draw(&self.ctx);
+                self.ctx.restore();
+            } else if self.state_timer % 20 > 10 {
+                self.ship.draw(&self.ctx);
+            }
+        }
+        
+        if scene == GameState::Playing && self.ship.weapon.beam_active() {
+            let (origin, direction) = self.laser_ray();
+            let boss = self.boss.as_ref().and_then(|boss| boss.ray_hit(&origin, &direction, LASER_RANGE));
+            let length = ray_cast(&origin, &direction, LASER_RANGE, &self.asteroids)
+                .into_iter()
+                .chain(boss)
+                .map(|(_, distance)| distance)
+                .fold(LASER_RANGE, f64::min);
+            draw_beam(&self.ctx, &origin, &direction, length, &theme);
+        }
+
+        for bullet in &self.bullets {
+            bullet.draw(&self.ctx);
+        }
+
+        for missile in &self.missiles {
+            missile.draw(&self.ctx, &theme);
+        }
+
+        for mine in &self.mines {
+            mine.draw(&self.ctx, &theme);
+        }
+
+        for blast in &self.blasts {
+            blast.draw(&self.ctx, &theme);
+        }
+
+        self.ctx.set_fill_style_str(theme.special);
+        for bullet in &self.enemy_bullets {
+            bullet.draw(&self.ctx);
+        }
+        self.ctx.set_fill_style_str("white");
+
+        if let Some(saucer) = &self.saucer {
+            saucer.draw(&self.ctx, &theme);
+        }
+
+        if let Some(boss) = &self.boss {
+            boss.draw(&self.ctx, &theme);
+        }
+
+        for power_up in &self.power_ups {
+            power_up.draw(&self.ctx, &theme);
+        }
+        
+        for asteroid in &self.asteroids {
+            asteroid.draw(&self.ctx, &theme);
+        }
+
+        for well in &self.gravity_wells {
+            well.draw(&self.ctx, &theme);
+        }
+
+        self.particles.draw(&self.ctx, &theme);
+
+        for shock_wave in &self.shock_waves {
+            shock_wave.draw(&self.ctx, &theme);
+        }
+        self.ctx.restore();
+
+        self.ctx.set_font(&self.hud_font(&theme, 16.0));
+        let mirrored = self.disruptions.has(DisruptionType::MirrorWorld);
+        for popup in &self.popups {
+            popup.draw(&self.ctx, mirrored, theme.highlight);
+        }
+
+        if scene == GameState::GameOver {
+            self.render_menu(&theme);
+        }
+
+        self.render_pause_overlay(&theme);
+    }
+
+    // Free bullet slots as pips, then the heat gauge when overheating is on,
+    // stacked down from `top` on the trailing edge
+    fn render_weapon_status(&self, theme: &Theme, x: f64, rtl: bool, top: f64) {
+        let weapon = &self.settings.weapon;
+        let pip_size = 6.0 * theme.hud_scale;
+        let pip_gap = 4.0 * theme.hud_scale;
+        let mut y = top;
+
+        self.ctx.save();
+        self.ctx.set_stroke_style_str("white");
+        self.ctx.set_fill_style_str("white");
+
+        // Only worth drawing for a small, fixed magazine
+        if (1..=16).contains(&weapon.max_bullets) {
+            let free = weapon.free_slots(self.bullets.len());
+            for pip in 0..weapon.max_bullets {
+                let offset = pip as f64 * (pip_size + pip_gap);
+                let pip_x = if rtl { x + offset } else { x - pip_size - offset };
+                if pip < free {
+                    self.ctx.fill_rect(pip_x, y, pip_size, pip_size);
+                } else {
+                    self.ctx.stroke_rect(pip_x, y, pip_size, pip_size);
+                }
+            }
+            y += pip_size + pip_gap;
+        }
+
+        if weapon.overheat {
+            let state = &self.ship.weapon;
+            let bar_width = 100.0 * theme.hud_scale;
+            let bar_height = 5.0 * theme.hud_scale;
+            let bar_x = if rtl { x } else { x - bar_width };
+            let filled = bar_width * state.heat / MAX_HEAT;
+            let fill_x = if rtl { bar_x } else { bar_x + bar_width - filled };
+            if state.overheated {
+                self.ctx.set_fill_style_str(theme.special);
+            }
+            self.ctx.stroke_rect(bar_x, y, bar_width, bar_height);
+            self.ctx.fill_rect(fill_x, y, filled, bar_height);
+
+            if state.overheated {
+                self.ctx.set_font(&self.hud_font(theme, 14.0));
+                self.ctx.set_text_align(if rtl { "left" } else { "right" });
+                self.hud_text(theme, &self.i18n.text("hud-overheated"), x, y + bar_height + 16.0 * theme.hud_scale);
+            }
+        }
+        self.ctx.restore();
+    }
+
+    // Each active disruption, centred under the score, with a bar that
+    // empties as the effect wears off
+    fn render_disruptions(&self, theme: &Theme, top: f64) {
+        let row_height = 34.0 * theme.hud_scale;
+        let bar_width = 160.0 * theme.hud_scale;
+        let bar_height = 5.0 * theme.hud_scale;
+
+        self.ctx.save();
+        self.ctx.set_font(&self.hud_font(theme, 24.0));
+        self.ctx.set_text_align("center");
+        self.ctx.set_fill_style_str(theme.special);
+        self.ctx.set_stroke_style_str(theme.special);
+        for (row, effect) in self.disruptions.active.iter().enumerate() {
+            let y = top + row as f64 * row_height;
+            if let Some(key) = effect.kind.label_key() {
+                self.hud_text(theme, &self.i18n.text(key), 400.0, y);
+            }
+
+            let bar_x = 400.0 - bar_width / 2.0;
+            let bar_y = y + 6.0 * theme.hud_scale;
+            self.ctx.stroke_rect(bar_x, bar_y, bar_width, bar_height);
+            let filled = bar_width * effect.fraction_left();
+            self.ctx.fill_rect(400.0 - filled / 2.0, bar_y, filled, bar_height);
+        }
+        self.ctx.restore();
+    }
+
+    // Boss name over its health bar at the top centre, with ticks where the
+    // phases change
+    fn render_boss_health(&self, theme: &Theme) {
+        let boss = match &self.boss {
+            Some(boss) => boss,
+            None => return,
+        };
+        let bar_width = 240.0 * theme.hud_scale;
+        let bar_height = 8.0 * theme.hud_scale;
+        let bar_x = 400.0 - bar_width / 2.0;
+        let bar_y = 40.0 + 6.0 * theme.hud_scale;
+
+        self.ctx.save();
+        self.ctx.set_font(&self.hud_font(theme, 18.0));
+        self.ctx.set_text_align("center");
+        self.ctx.set_fill_style_str(theme.special);
+        self.ctx.set_stroke_style_str("white");
+        self.hud_text(theme, &self.i18n.text(boss.definition.label_key), 400.0, 40.0);
+        // Drains toward the leading edge
+        let filled = bar_width * boss.health_fraction();
+        let fill_x = if self.i18n.is_rtl() { bar_x + bar_width - filled } else { bar_x };
+        self.ctx.fill_rect(fill_x, bar_y, filled, bar_height);
+        self.ctx.stroke_rect(bar_x, bar_y, bar_width, bar_height);
+        self.ctx.begin_path();
+        for third in [1.0, 2.0] {
+            let x = bar_x + bar_width * third / 3.0;
+            self.ctx.move_to(x, bar_y);
+            self.ctx.line_to(x, bar_y + bar_height);
+        }
+        self.ctx.stroke();
+        self.ctx.restore();
+    }
+
+    // Selected weapon and its remaining ammo in the bottom trailing corner;
+    // the laser's charge is shown as a percentage
+    fn render_current_weapon(&self, theme: &Theme, x: f64, align: &str) {
+        let kind = self.ship.weapon.current;
+        let label = self.i18n.text(kind.label_key());
+        let text = match (self.ship.weapon.ammo(kind), kind.max_ammo()) {
+            (Some(ammo), Some(max)) if kind == WeaponKind::Laser => {
+                let percent = (ammo * 100 + max - 1) / max;
+                format!("{}  {}", label, self.i18n.format("value-percent", &[("value", percent as i64)]))
+            }
+            (Some(ammo), _) => format!("{}  {}", label, self.i18n.number(ammo as i64)),
+            _ => label,
+        };
+
+        self.ctx.save();
+        self.ctx.set_font(&self.hud_font(theme, 16.0));
+        self.ctx.set_text_align(align);
+        self.hud_text(theme, &text, x, 580.0);
+        self.ctx.restore();
+    }
+
+    // Combo multiplier under the level, with a bar for the time left to keep it
+    fn render_combo(&self, theme: &Theme, x: f64, rtl: bool, y: f64) {
+        if self.combo.multiplier() <= 1 {
+            return;
+        }
+        let bar_width = 100.0 * theme.hud_scale;
+        let bar_height = 4.0 * theme.hud_scale;
+
+        self.ctx.save();
+        self.ctx.set_font(&self.hud_font(theme, 20.0));
+        self.ctx.set_fill_style_str(theme.highlight);
+        self.ctx.set_stroke_style_str(theme.highlight);
+        let text = self.i18n.format("hud-combo", &[("multiplier", self.combo.multiplier() as i64)]);
+        self.hud_text(theme, &text, x, y);
+
+        let bar_x = if rtl { x - bar_width } else { x };
+        let bar_y = y + 6.0 * theme.hud_scale;
+        self.ctx.stroke_rect(bar_x, bar_y, bar_width, bar_height);
+        let filled = bar_width * self.combo.fraction_left();
+        let fill_x = if rtl { x - filled } else { x };
+        self.ctx.fill_rect(fill_x, bar_y, filled, bar_height);
+        self.ctx.restore();
+    }
+
+    // Active power-ups listed up from the bottom of the leading edge, each
+    // with a bar that empties as the effect runs out
+    fn render_power_up_timers(&self, theme: &Theme, x: f64, align: &str) {
+        let row_height = 26.0 * theme.hud_scale;
+        let bar_width = 80.0 * theme.hud_scale;
+        let bar_height = 5.0 * theme.hud_scale;
+
+        self.ctx.save();
+        self.ctx.set_font(&self.hud_font(theme, 14.0));
+        self.ctx.set_text_align(align);
+        self.ctx.set_fill_style_str(theme.highlight);
+        self.ctx.set_stroke_style_str(theme.highlight);
+        for (row, effect) in self.ship.power_ups.active.iter().enumerate() {
+            let y = 580.0 - row as f64 * row_height;
+            self.hud_text(theme, &self.i18n.text(effect.kind.label_key()), x, y - bar_height - 4.0);
+
+            let bar_x = if align == "right" { x - bar_width } else { x };
+            self.ctx.stroke_rect(bar_x, y - bar_height, bar_width, bar_height);
+            let filled = bar_width * effect.fraction_left();
+            let fill_x = if align == "right" { x - filled } else { x };
+            self.ctx.fill_rect(fill_x, y - bar_height, filled, bar_height);
+        }
+        self.ctx.restore();
+    }
+
+    fn render_title(&self, theme: &Theme) {
+        for asteroid in &self.asteroids {
+            asteroid.draw(&self.ctx, theme);
+        }
+
+        self.render_menu(theme);
+    }
+
+    fn render_pause_overlay(&self, theme: &Theme) {
+        if self.state != GameState::Paused {
+            return;
+        }
+
+        self.ctx.save();
+        self.ctx.set_global_alpha(0.75);
+        self.ctx.set_fill_style_str("black");
+        self.ctx.fill_rect(0.0, 0.0, 800.0, 600.0);
+        self.ctx.restore();
+
+        self.render_menu(theme);
+    }
+
+    pub fn rotate(&mut self, angle: f64) {
+        if self.controls_locked() {
+            return;
+        }
+        
+        let actual_angle = if self.disruptions.has(DisruptionType::Scrambled) {
+            -angle // Reverse controls
+        } else {
+            angle
+        };
+        
+        self.ship.rotation += actual_angle;
+    }
+
+    pub fn thrust(&mut self) {
+        if self.controls_locked() {
+            return;
+        }
+        
+        let thrust = 0.1;
+        let direction = if self.disruptions.has(DisruptionType::Scrambled) {
+            // Scrambled: thrust goes sideways
+            self.ship.rotation + PI / 2.0
+        } else {
+            // Normal thrust
+            self.ship.rotation
+        };
+        
+        self.ship.velocity.x += thrust * direction.sin();
+        self.ship.velocity.y += -thrust * direction.cos();
+        self.particles.exhaust(&self.ship.position, direction, &self.ship.velocity);
+        self.play_sound("thrust-sound");
+    }
+
+    pub fn shoot(&mut self) {
+        if self.controls_locked() {
+            return;
+        }
+        
+        let weapon = &self.settings.weapon;
+        let live_bullets = self.bullets.len();
+        if !self.ship.weapon.ready(weapon, live_bullets) {
+            return;
+        }
+
+        let kind = self.ship.weapon.current;
+        let piercing = self.ship.power_ups.has(PowerUpKind::Piercing);
+        let beam_was_on = self.ship.weapon.beam_active();
+        match kind {
+            WeaponKind::Blaster => {
+                // A spread fills whatever bullet slots are free, centre shot first
+                let offsets: &[f64] = if self.ship.power_ups.has(PowerUpKind::SpreadShot) {
+                    &[0.0, -SPREAD_ANGLE, SPREAD_ANGLE]
+                } else {
+                    &[0.0]
+                };
+                for offset in offsets.iter().take(weapon.free_slots(live_bullets)) {
+                    self.bullets.push(Bullet::new(&self.ship, *offset, piercing, &self.settings.bullets));
+                }
+            }
+            WeaponKind::Laser => {}
+            WeaponKind::Missiles => self.missiles.push(Missile::new(&self.ship.position, self.ship.rotation)),
+            WeaponKind::Mines => {
+                // The oldest mine is recalled once the field is full
+                if self.mines.len() >= MAX_MINES {
+                    self.mines.remove(0);
+                }
+                self.mines.push(Mine::new(&self.ship.position, self.ship.rotation));
+            }
+            WeaponKind::Shotgun => {
+                // With few slots free, the pellets left still fan out across the whole spread
+                let pellets = SHOTGUN_PELLETS.min(weapon.free_slots(live_bullets));
+                for pellet in 0..pellets {
+                    let spread = if pellets > 1 { pellet as f64 / (pellets - 1) as f64 * 2.0 - 1.0 } else { 0.0 };
+                    let pellet = Bullet::pellet(&self.ship, spread * SHOTGUN_SPREAD, piercing, &self.settings.bullets);
+                    self.bullets.push(pellet);
+                }
+            }
+        }
+
+        // Rapid fire halves the cooldown
+        let rapid_fire = self.ship.power_ups.has(PowerUpKind::RapidFire);
+        let overheated = self.ship.weapon.fired(&self.settings.weapon, rapid_fire);
+        if !beam_was_on {
+            self.play_sound(kind.sound_id());
+        }
+        if overheated {
+            self.emit(GameEvent::WeaponOverheated);
+            self.play_sound("overheat-sound");
+        }
+    }
+
+    pub fn next_weapon(&mut self) {
+        self.switch_weapon(1);
+    }
+
+    // Accepts a weapon name such as "laser"; returns false for unknown names
+    // or a weapon with no ammo left
+    pub fn select_weapon(&mut self, name: &str) -> bool {
+        match WeaponKind::from_name(name) {
+            Some(kind) if self.ship.weapon.has_ammo(kind) => {
+                self.select(kind);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get_weapon(&self) -> String {
+        self.ship.weapon.current.name().to_string()
+    }
+
+    // Holds the shield up until called again with false
+    pub fn set_shield(&mut self, raised: bool) {
+        self.shield_held = raised;
+    }
+
+    pub fn get_shield_energy(&self) -> f64 {
+        self.ship.shield.energy / MAX_ENERGY
+    }
+
+    pub fn set_fire_cooldown(&mut self, seconds: f64) {
+        self.settings.weapon.cooldown_frames = (seconds.max(0.0) * 60.0).round() as i32;
+    }
+
+    // Points between extra lives; 0 turns them off
+    pub fn set_extra_life_interval(&mut self, points: i32) {
+        self.settings.extra_lives.interval = points.max(0);
+    }
+
+    // Extra lives stop at this many lives; 0 removes the cap
+    pub fn set_max_lives(&mut self, count: i32) {
+        self.settings.extra_lives.max_lives = count.max(0);
+    }
+
+    // 0 removes the limit; 4 reproduces the classic arcade rule
+    pub fn set_max_bullets(&mut self, count: u32) {
+        self.settings.weapon.max_bullets = count as usize;
+    }
+
+    // Sustained fire builds heat and locks the gun until it cools down
+    pub fn set_overheat(&mut self, enabled: bool) {
+        self.settings.weapon.overheat = enabled;
+        self.ship.weapon.heat = 0.0;
+        self.ship.weapon.overheated = false;
+    }
+
+    // Muzzle speed in pixels per second
+    pub fn set_bullet_speed(&mut self, pixels_per_second: f64) {
+        self.settings.bullets.muzzle_speed = pixels_per_second.max(60.0) / 60.0;
+    }
+
+    pub fn set_bullet_lifetime(&mut self, seconds: f64) {
+        self.settings.bullets.lifetime = (seconds.max(0.0) * 60.0).round().max(1.0) as i32;
+    }
+
+    // Distance in pixels before a bullet expires; 0 removes the limit
+    pub fn set_bullet_range(&mut self, pixels: f64) {
+        self.settings.bullets.range = if pixels > 0.0 { pixels } else { f64::INFINITY };
+    }
+
+    pub fn set_bullet_inherit_velocity(&mut self, enabled: bool) {
+        self.settings.bullets.inherit_velocity = enabled;
+    }
+
+    pub fn set_bullet_wrap(&mut self, enabled: bool) {
+        self.settings.bullets.wrap = enabled;
+    }
+
+    pub fn set_bullet_self_hit(&mut self, enabled: bool) {
+        self.settings.bullets.self_hit = enabled;
+    }
+
+    // Relative chance of a special rock causing the named disruption, such as
+    // "blackout"; 0 turns it off. Returns false for unknown names.
+    pub fn set_disruption_weight(&mut self, name: &str, weight: f64) -> bool {
+        match DisruptionType::from_name(name) {
+            Some(kind) => self.settings.disruptions.set_weight(kind, weight),
+            None => false,
+        }
+    }
+
+    // Outward push from special-rock shock waves, in pixels per frame at the centre
+    pub fn set_shock_wave_push(&mut self, strength: f64) {
+        self.settings.shock_waves.push = strength.max(0.0);
+    }
+
+    pub fn set_shock_wave_split(&mut self, enabled: bool) {
+        self.settings.shock_waves.split_large = enabled;
+    }
+
+    // Large rocks break into three fragments instead of two
+    pub fn set_three_way_split(&mut self, enabled: bool) {
+        self.settings.three_way_split = enabled;
+    }
+
+    pub fn set_asteroid_collisions(&mut self, enabled: bool) {
+        self.settings.collisions.enabled = enabled;
+    }
+
+    // 1.0 for perfectly elastic bounces between rocks, 0.0 for none
+    pub fn set_asteroid_restitution(&mut self, restitution: f64) {
+        self.settings.collisions.restitution = restitution.clamp(0.0, 1.0);
+    }
+
+    // Takes effect from the next level
+    pub fn set_gravity_wells(&mut self, enabled: bool) {
+        self.settings.gravity_wells = enabled;
+    }
+
+    // Drop chance per destroyed rock for levels 1, 2, 3...; the last value
+    // applies to all later levels
+    pub fn set_power_up_drop_rates(&mut self, rates: Vec<f64>) {
+        self.settings.power_ups.drop_rates = rates.into_iter().map(|rate| rate.clamp(0.0, 1.0)).collect();
+    }
+
+    // Jump to a random spot after a short warp; returns false if the drive
+    // is cooling down or controls are unavailable
+    pub fn hyperspace(&mut self) -> bool {
+        if self.controls_locked() || self.state != GameState::Playing || !self.ship.hyperspace.ready() {
+            return false;
+        }
+
+        self.ship.hyperspace.engage(self.settings.hyperspace.cooldown_frames);
+        self.emit(GameEvent::HyperspaceJump);
+        self.play_sound("hyperspace-sound");
+        true
+    }
+
+    pub fn reset(&mut self) {
+        self.ship = Ship::new(400.0, 300.0);
+        self.bullets.clear();
+        self.clear_ordnance();
+        self.asteroids.clear();
+        self.shock_waves.clear();
+        self.gravity_wells.clear();
+        self.boss = None;
+        self.particles.clear();
+        self.power_ups.clear();
+        self.score = 0;
+        self.lives = self.settings.difficulty.starting_lives();
+        self.extra_life_flash = 0;
+        self.seed = self.next_seed.take().unwrap_or_else(random_seed);
+        rng::seed(self.seed);
+        self.combo.reset();
+        self.near_misses.clear();
+        self.popups.clear();
+        self.level = 1;
+        self.state_timer = 0;
+        self.clear_saucers();
+        self.disruptions = Disruptions::new();
+        self.release_inputs();
+        
+        self.spawn_level_asteroids();
+        self.transition(GameState::Playing);
+    }
+
+    pub fn start(&mut self) {
+        if self.state == GameState::Title {
+            self.reset();
+        }
+    }
+
+    pub fn pause(&mut self) {
+        if self.state.can_pause() {
+            self.transition(GameState::Paused);
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.state == GameState::Paused {
+            self.transition(self.paused_from);
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        if self.state == GameState::Paused {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    pub fn state(&self) -> String {
+        self.state.name().to_string()
+    }
+
+    // Events since the last call, oldest first, as "kind:detail" strings
+    pub fn drain_events(&mut self) -> Vec<String> {
+        self.events.drain(..).map(|event| event.describe()).collect()
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.state == GameState::GameOver
+    }
+
+    pub fn get_score(&self) -> i32 {
+        self.score
+    }
+
+    pub fn get_level(&self) -> i32 {
+        self.level
+    }
+
+    pub fn get_seed(&self) -> u32 {
+        self.seed
+    }
+
+    // Plays the next new game from this seed; with the same inputs it
+    // unfolds the same way
+    pub fn set_seed(&mut self, seed: u32) {
+        self.next_seed = Some(seed);
+    }
+
+    // For pages with their own name field, such as on touch screens; the
+    // name is used the next time a high score is saved
+    pub fn set_player_name(&mut self, name: &str) {
+        self.player_name = clean_name(name);
+    }
+
+    // Best first, as tab-separated "name, score, level, difficulty, date, seed" rows
+    pub fn get_high_scores(&self) -> Vec<String> {
+        self.high_scores.entries().iter().map(HighScoreEntry::fields).collect()
+    }
 
+    pub fn clear_high_scores(&mut self) {
+        self.high_scores.clear();
+    }
+
+    // Keys use KeyboardEvent.key names; returns true when the game used the key
+    pub fn key_down(&mut self, key: &str) -> bool {
+        if self.menu_visible() && self.menu.page() == MenuPage::NameEntry && self.type_name(key) {
+            return true;
+        }
+        if self.menu_visible() {
+            let input = match key {
+                "ArrowUp" => MenuInput::Up,
+                "ArrowDown" => MenuInput::Down,
+                "ArrowLeft" => MenuInput::Left,
+                "ArrowRight" => MenuInput::Right,
+                "Enter" | " " => MenuInput::Select,
+                "Escape" | "Backspace" => MenuInput::Back,
+                "p" | "P" if self.state == GameState::Paused => MenuInput::Back,
+                // Still starts a new game from the pause and game over menus
+                "r" | "R" if matches!(self.state, GameState::Paused | GameState::GameOver) => {
+                    self.reset();
+                    return true;
+                }
+                _ => return false,
+            };
+            self.menu_input(input);
+            return true;
+        }
+
+        self.held_keys.insert(key.to_string());
+        match key {
+            // While rapid fire is active, held Space auto-fires instead
+            " " if !self.ship.power_ups.has(PowerUpKind::RapidFire) => self.shoot(),
+            " " => {}
+            "ArrowDown" | "h" | "H" => {
+                self.hyperspace();
+            }
+            "q" | "Q" => self.switch_weapon(-1),
+            "e" | "E" => self.next_weapon(),
+            "1" | "2" | "3" | "4" | "5" => {
+                let slot = key.parse().unwrap_or(0);
+                if let Some(kind) = WeaponKind::from_slot(slot) {
+                    self.select_weapon(kind.name());
+                }
+            }
+            "p" | "P" | "Escape" => self.pause(),
+            "r" | "R" => self.reset(),
+            "m" | "M" => {
+                let scheme = if self.settings.control_scheme == ControlScheme::MouseAim {
+                    ControlScheme::Keyboard
+                } else {
+                    ControlScheme::MouseAim
+                };
+                self.apply_control_scheme(scheme);
+            }
+            "ArrowLeft" | "ArrowRight" | "ArrowUp" | "s" | "S" => {}
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn key_up(&mut self, key: &str) {
+        self.held_keys.remove(key);
+    }
+
+    pub fn set_control_scheme(&mut self, scheme: &str) -> bool {
+        match ControlScheme::from_name(scheme) {
+            Some(scheme) => {
+                self.apply_control_scheme(scheme);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_control_scheme(&self) -> String {
+        self.settings.control_scheme.name().to_string()
+    }
+
+    pub fn set_volume(&mut self, volume: f64) {
+        self.settings.volume = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn get_volume(&self) -> f64 {
+        self.settings.volume
+    }
+
+    // Takes effect from the next new game
+    pub fn set_difficulty(&mut self, difficulty: &str) -> bool {
+        match Difficulty::from_name(difficulty) {
+            Some(difficulty) => {
+                self.settings.difficulty = difficulty;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_difficulty(&self) -> String {
+        self.settings.difficulty.name().to_string()
+    }
+
+    pub fn set_particle_quality(&mut self, quality: &str) -> bool {
+        match ParticleQuality::from_name(quality) {
+            Some(quality) => {
+                self.settings.particles = quality;
+                self.particles.set_quality(quality);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_particle_quality(&self) -> String {
+        self.settings.particles.name().to_string()
+    }
+
+    pub fn set_reduced_flash(&mut self, enabled: bool) {
+        self.settings.accessibility.reduced_flash = enabled;
+    }
+
+    pub fn set_palette(&mut self, palette: &str) -> bool {
+        match Palette::from_name(palette) {
+            Some(palette) => {
+                self.settings.accessibility.palette = palette;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_palette(&self) -> String {
+        self.settings.accessibility.palette.name().to_string()
+    }
+
+    pub fn set_high_contrast(&mut self, enabled: bool) {
+        self.settings.accessibility.high_contrast = enabled;
+    }
+
+    pub fn set_hud_scale(&mut self, scale: f64) {
+        self.settings.accessibility.hud_scale = scale.clamp(HUD_SCALE_MIN, HUD_SCALE_MAX);
+    }
+
+    // Marks special rocks with a warning shape as well as colour
+    pub fn set_hazard_markers(&mut self, enabled: bool) {
+        self.settings.accessibility.hazard_markers = enabled;
+    }
+
+    pub fn set_hyperspace_cooldown(&mut self, seconds: f64) {
+        self.settings.hyperspace.cooldown_frames = (seconds.max(0.0) * 60.0).round() as i32;
+    }
+
+    // Chance (0-1) that a jump destroys the ship
+    pub fn set_hyperspace_malfunction_chance(&mut self, chance: f64) {
+        self.settings.hyperspace.malfunction_chance = chance.clamp(0.0, 1.0);
+    }
+
+    // Prefer landing spots away from rocks instead of anywhere at random
+    pub fn set_hyperspace_safe_landing(&mut self, enabled: bool) {
+        self.settings.hyperspace.safe_landing = enabled;
+    }
+
+    // Accepts tags like "fr" or "fr-CA"; returns false if only English matched
+    pub fn set_locale(&mut self, locale: &str) -> bool {
+        self.i18n.set_locale(locale)
+    }
+
+    pub fn get_locale(&self) -> String {
+        self.i18n.locale().to_string()
+    }
+
+    // Registers or extends a catalog in the same format as src/locales/*.ftl
+    pub fn load_locale(&mut self, locale: &str, source: &str) {
+        self.i18n.load(locale, source);
+    }
+
+    // Pointer and mouse coordinates are page (client) coordinates
+    pub fn pointer_down(&mut self, id: i32, client_x: f64, client_y: f64) {
+        let point = self.client_to_world(client_x, client_y);
+        if self.menu_visible() {
+            self.menu_click(&point);
+            return;
+        }
+
+        // Touching the screen switches keyboard and mouse players to on-screen controls
+        if !self.settings.control_scheme.uses_touch() {
+            self.apply_control_scheme(ControlScheme::VirtualStick);
+        }
+        self.touch.pointer_down(id, point.x, point.y, self.settings.control_scheme);
+    }
+
+    pub fn pointer_move(&mut self, id: i32, client_x: f64, client_y: f64) {
+        let point = self.client_to_world(client_x, client_y);
+        self.touch.pointer_move(id, point.x, point.y);
+    }
+
+    pub fn pointer_up(&mut self, id: i32) {
+        self.touch.pointer_up(id);
+    }
+
+    pub fn set_touch_layout(&mut self, stick_x: f64, stick_y: f64, fire_x: f64, fire_y: f64, thrust_x: f64, thrust_y: f64) {
+        self.touch.layout.stick_center = Vector { x: stick_x, y: stick_y };
+        self.touch.layout.fire_center = Vector { x: fire_x, y: fire_y };
+        self.touch.layout.thrust_center = Vector { x: thrust_x, y: thrust_y };
+    }
+
+    pub fn set_touch_sizes(&mut self, stick_radius: f64, button_radius: f64) {
+        self.touch.layout.stick_radius = stick_radius.max(10.0);
+        self.touch.layout.button_radius = button_radius.max(10.0);
+    }
+
+    pub fn set_touch_opacity(&mut self, opacity: f64) {
+        self.touch.layout.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    pub fn mouse_move(&mut self, client_x: f64, client_y: f64) {
+        let point = self.client_to_world(client_x, client_y);
+
+        // Hovering highlights menu items
+        if self.menu_visible() {
+            let item_count = self.menu.items(&self.settings, &self.i18n).len();
+            if let Some(index) = self.menu.item_at(&point, item_count, self.menu_lines().len()) {
+                self.menu.selected = index;
+            }
+        }
+        self.mouse.target = Some(point);
+    }
+
+    pub fn mouse_down(&mut self, button: i16) {
+        if self.menu_visible() {
+            if let Some(point) = self.mouse.target.clone() {
+                self.menu_click(&point);
+            }
+        } else if self.settings.control_scheme == ControlScheme::MouseAim {
+            if button == 1 {
+                self.hyperspace(); // Middle click
+            } else {
+                self.mouse.button_down(button);
+            }
+        }
+    }
+
+    pub fn mouse_up(&mut self, button: i16) {
+        self.mouse.button_up(button);
+    }
+
+    pub fn mouse_leave(&mut self) {
+        self.mouse.clear();
+    }
+
+    // Maximum radians per frame the ship turns toward the cursor
+    pub fn set_mouse_turn_rate(&mut self, rate: f64) {
+        self.mouse.turn_rate = rate.max(0.0);
+    }
+}
+
+fn play_sound(audio_context: &web_sys::AudioContext, sound_id: &str, volume: f64) {
     // Create oscillator
     let oscillator = match audio_context.create_oscillator() {
         Ok(osc) => osc,
@@ -692,14 +3437,437 @@ fn play_sound(sound_id: &str) {
             );
             let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.4);
         }
+        "pause-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sine);
+            oscillator.frequency().set_value(392.0); // G4 note
+            gain.gain().set_value(0.15);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                261.63, // C4 note
+                audio_context.current_time() + 0.15
+            );
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.2);
+        }
+        "resume-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sine);
+            oscillator.frequency().set_value(261.63); // C4 note
+            gain.gain().set_value(0.15);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                392.0, // G4 note
+                audio_context.current_time() + 0.15
+            );
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.2);
+        }
+        "game-over-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Triangle);
+            oscillator.frequency().set_value(220.0); // A3 note
+            gain.gain().set_value(0.3);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                55.0, // A1 note
+                audio_context.current_time() + 0.5
+            ); // Long slide down
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.5);
+        }
+        "menu-move-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(660.0); // E5 note
+            gain.gain().set_value(0.05);
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.05);
+        }
+        "hyperspace-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sine);
+            oscillator.frequency().set_value(1200.0);
+            gain.gain().set_value(0.15);
+            let _ = oscillator.frequency().exponential_ramp_to_value_at_time(
+                150.0,
+                audio_context.current_time() + 0.4
+            ); // Falling sweep as the ship collapses away
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.45);
+        }
+        "shield-up-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Triangle);
+            oscillator.frequency().set_value(300.0);
+            gain.gain().set_value(0.12);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                600.0,
+                audio_context.current_time() + 0.15
+            ); // Rising hum as the bubble forms
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.2);
+        }
+        "shield-hit-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(160.0);
+            gain.gain().set_value(0.25);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                90.0,
+                audio_context.current_time() + 0.2
+            ); // Dull thud off the bubble
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.25);
+        }
+        "saucer-large-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(110.0);
+            gain.gain().set_value(0.06);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                140.0,
+                audio_context.current_time() + 0.12
+            ); // Slow warble
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.15);
+        }
+        "saucer-small-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(620.0);
+            gain.gain().set_value(0.05);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                760.0,
+                audio_context.current_time() + 0.08
+            ); // Fast, high warble
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.1);
+        }
+        "enemy-shoot-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(330.0); // E4, lower than the player's shot
+            gain.gain().set_value(0.1);
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.1);
+        }
+        "power-up-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Triangle);
+            oscillator.frequency().set_value(659.25); // E5 note
+            gain.gain().set_value(0.15);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                1318.5, // E6 note
+                audio_context.current_time() + 0.2
+            ); // Bright octave rise
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.3);
+        }
+        "overheat-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sawtooth);
+            oscillator.frequency().set_value(600.0);
+            gain.gain().set_value(0.12);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                200.0,
+                audio_context.current_time() + 0.35
+            ); // Sagging whine as the gun locks up
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.4);
+        }
+        "laser-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sawtooth);
+            oscillator.frequency().set_value(1200.0);
+            gain.gain().set_value(0.06);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                900.0,
+                audio_context.current_time() + 0.2
+            ); // Thin, buzzing beam
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.25);
+        }
+        "missile-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Triangle);
+            oscillator.frequency().set_value(150.0);
+            gain.gain().set_value(0.15);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                500.0,
+                audio_context.current_time() + 0.3
+            ); // Rising whoosh as the motor lights
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.35);
+        }
+        "mine-drop-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(220.0); // A3 note
+            gain.gain().set_value(0.1);
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.08);
+        }
+        "mine-explosion-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sawtooth);
+            oscillator.frequency().set_value(80.0);
+            gain.gain().set_value(0.35);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                30.0,
+                audio_context.current_time() + 0.45
+            ); // Deep boom
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.5);
+        }
+        "shotgun-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sawtooth);
+            oscillator.frequency().set_value(300.0);
+            gain.gain().set_value(0.2);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                120.0,
+                audio_context.current_time() + 0.12
+            ); // Short, heavy blast
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.15);
+        }
+        "swallow-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sine);
+            oscillator.frequency().set_value(200.0);
+            gain.gain().set_value(0.2);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                20.0,
+                audio_context.current_time() + 0.6
+            ); // Falling away into the dark
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.6);
+        }
+        "metal-hit-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(1200.0);
+            gain.gain().set_value(0.12);
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.06);
+        }
+        "metal-break-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(600.0);
+            gain.gain().set_value(0.2);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                90.0,
+                audio_context.current_time() + 0.35
+            ); // Grinding crunch
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.4);
+        }
+        "ice-shatter-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Triangle);
+            oscillator.frequency().set_value(2000.0);
+            gain.gain().set_value(0.15);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                3000.0,
+                audio_context.current_time() + 0.1
+            ); // Bright tinkle
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.15);
+        }
+        "crystal-ricochet-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sine);
+            oscillator.frequency().set_value(1760.0); // A6 note
+            gain.gain().set_value(0.15);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                2640.0,
+                audio_context.current_time() + 0.1
+            ); // Ringing ping
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.2);
+        }
+        "crystal-shatter-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sine);
+            oscillator.frequency().set_value(2640.0);
+            gain.gain().set_value(0.2);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                880.0, // A5 note
+                audio_context.current_time() + 0.3
+            );
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.35);
+        }
+        "dense-break-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Triangle);
+            oscillator.frequency().set_value(90.0);
+            gain.gain().set_value(0.3);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                50.0,
+                audio_context.current_time() + 0.2
+            ); // Heavy thud
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.25);
+        }
+        "extra-life-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(523.25); // C5 note
+            gain.gain().set_value(0.15);
+            let now = audio_context.current_time();
+            // Quick rising fanfare: C5, E5, G5, C6
+            for (step, note) in [659.25, 783.99, 1046.5].iter().enumerate() {
+                let _ = oscillator.frequency().set_value_at_time(*note, now + 0.1 * (step + 1) as f64);
+            }
+            let _ = gain.gain().set_value_at_time(0.15, now + 0.4);
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, now + 0.6);
+        }
+        "boss-warning-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sawtooth);
+            oscillator.frequency().set_value(110.0); // A2 note
+            gain.gain().set_value(0.25);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                220.0, // A3 note
+                audio_context.current_time() + 0.5
+            );
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                110.0,
+                audio_context.current_time() + 1.0
+            ); // Siren
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 1.0);
+        }
+        "boss-phase-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(330.0);
+            gain.gain().set_value(0.2);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                165.0,
+                audio_context.current_time() + 0.4
+            ); // Angry growl
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.45);
+        }
+        "boss-defeated-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sawtooth);
+            oscillator.frequency().set_value(160.0);
+            gain.gain().set_value(0.4);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                20.0,
+                audio_context.current_time() + 1.2
+            ); // Long, collapsing boom
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 1.2);
+        }
+        "combo-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Triangle);
+            oscillator.frequency().set_value(880.0); // A5 note
+            gain.gain().set_value(0.12);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                1320.0, // E6 note
+                audio_context.current_time() + 0.12
+            );
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.2);
+        }
+        "near-miss-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sine);
+            oscillator.frequency().set_value(1200.0);
+            gain.gain().set_value(0.1);
+            let _ = oscillator.frequency().exponential_ramp_to_value_at_time(
+                400.0,
+                audio_context.current_time() + 0.25
+            ); // Whoosh
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.25);
+        }
+        "gravity-pulse-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sine);
+            oscillator.frequency().set_value(60.0);
+            gain.gain().set_value(0.3);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                30.0,
+                audio_context.current_time() + 0.5
+            ); // Sinking rumble
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.5);
+        }
+        "time-dilation-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Triangle);
+            oscillator.frequency().set_value(880.0); // A5 note
+            gain.gain().set_value(0.15);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                110.0, // A2 note
+                audio_context.current_time() + 0.5
+            ); // Winding down
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.5);
+        }
+        "blackout-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(120.0);
+            gain.gain().set_value(0.15);
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.2);
+        }
+        "magnetic-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sawtooth);
+            oscillator.frequency().set_value(50.0);
+            gain.gain().set_value(0.12);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                70.0,
+                audio_context.current_time() + 0.45
+            ); // Electric hum
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.45);
+        }
+        "mirror-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Sine);
+            oscillator.frequency().set_value(440.0); // A4 note
+            gain.gain().set_value(0.15);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                880.0, // A5 note
+                audio_context.current_time() + 0.15
+            );
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                440.0,
+                audio_context.current_time() + 0.3
+            ); // Up and back down again
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.35);
+        }
+        "menu-select-sound" => {
+            oscillator.set_type(web_sys::OscillatorType::Square);
+            oscillator.frequency().set_value(880.0); // A5 note
+            gain.gain().set_value(0.08);
+            let _ = oscillator.frequency().linear_ramp_to_value_at_time(
+                1318.5, // E6 note
+                audio_context.current_time() + 0.08
+            );
+            let _ = gain.gain().linear_ramp_to_value_at_time(0.0, audio_context.current_time() + 0.1);
+        }
         _ => return,
     }
 
+    // Master volume applied after each sound's own envelope
+    let master = match audio_context.create_gain() {
+        Ok(g) => g,
+        Err(_) => return,
+    };
+    master.gain().set_value(volume as f32);
+
     // Connect nodes
     let _ = oscillator.connect_with_audio_node(&gain);
-    let _ = gain.connect_with_audio_node(&audio_context.destination());
+    let _ = gain.connect_with_audio_node(&master);
+    let _ = master.connect_with_audio_node(&audio_context.destination());
 
-    // Start and stop the sound
+    // Start the sound and stop it once its envelope has played out
     let _ = oscillator.start();
-    let _ = oscillator.stop_with_when(audio_context.current_time() + 0.5);
-}
\ No newline at end of file
+    let _ = oscillator.stop_with_when(audio_context.current_time() + sound_duration(sound_id));
+}
+
+// Seconds until a sound's last ramp ends; most fit in half a second
+fn sound_duration(sound_id: &str) -> f64 {
+    match sound_id {
+        "swallow-sound" => 0.6,
+        "extra-life-sound" => 0.6,
+        "boss-warning-sound" => 1.0,
+        "boss-defeated-sound" => 1.2,
+        _ => 0.5,
+    }
+}
+
+impl Drop for Game {
+    fn drop(&mut self) {
+        // Detach the DOM listener before its closure is freed
+        if let Some(listener) = self.visibility_listener.take() {
+            if let Some(document) = web_sys::window().and_then(|win| win.document()) {
+                let _ = document.remove_event_listener_with_callback("visibilitychange", listener.as_ref().unchecked_ref());
+            }
+        }
+        if let Some(audio_context) = self.audio.take() {
+            let _ = audio_context.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_level(seed: u32, level: i32) -> Vec<(f64, f64, f64, f64, f64, Material, bool)> {
+        rng::seed(seed);
+        let mut definition = LevelDefinition::for_level(level);
+        let wells = std::mem::take(&mut definition.wells);
+        level_asteroids(&definition, &wells)
+            .iter()
+            .map(|rock| {
+                let Asteroid { position, velocity, size, material, is_special, .. } = rock;
+                (position.x, position.y, velocity.x, velocity.y, *size, *material, *is_special)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_level_layout() {
+        for level in [1, 3, 6] {
+            let layout = first_level(1234, level);
+            assert_eq!(layout.len(), LevelDefinition::for_level(level).asteroid_count as usize);
+            assert!(layout == first_level(1234, level));
+        }
+        assert!(first_level(1234, 1) != first_level(4321, 1));
+    }
+
+    #[test]
+    fn distance_is_measured_across_the_screen_edge() {
+        let left = Vector { x: 5.0, y: 300.0 };
+        let right = Vector { x: WORLD_WIDTH - 5.0, y: 300.0 };
+        assert_eq!(wrap_distance(&left, &right), 10.0);
+
+        let top = Vector { x: 400.0, y: 2.0 };
+        let bottom = Vector { x: 403.0, y: WORLD_HEIGHT - 2.0 };
+        assert_eq!(wrap_distance(&top, &bottom), 5.0);
+        assert_eq!(wrap_distance(&Vector { x: 100.0, y: 100.0 }, &Vector { x: 130.0, y: 140.0 }), 50.0);
+    }
+}
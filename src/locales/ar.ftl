@@ -0,0 +1,132 @@
+# Arabic (right-to-left, Arabic-Indic digits)
+-language-name = العربية
+-direction = rtl
+-digits = ٠١٢٣٤٥٦٧٨٩
+-group-separator = "٬"
+-min-grouping = 4
+
+hud-score = النقاط: { $score }
+hud-level = المستوى: { $level }
+hud-lives.zero = لا أرواح متبقية
+hud-lives.one = روح واحدة
+hud-lives.two = روحان
+hud-lives.few = { $count } أرواح
+hud-lives.many = { $count } روحًا
+hud-lives.other = { $count } روح
+hud-shield = الدرع
+hud-overheated = ارتفاع الحرارة
+hud-combo = سلسلة ×{ $multiplier }
+popup-points = +{ $points }
+popup-near-miss = نجاة بأعجوبة
+
+disruption-disabled = الأنظمة معطلة
+disruption-scrambled = التحكم مقلوب
+disruption-unstable = السفينة غير مستقرة
+disruption-gravity = نبضة جاذبية
+disruption-time = تمدد الزمن
+disruption-blackout = انقطاع الرؤية
+disruption-magnetic = حقل مغناطيسي
+disruption-mirror = عالم معكوس
+
+boss-titan = العملاق
+boss-mothership = السفينة الأم
+
+power-up-rapid-fire = إطلاق سريع
+power-up-spread-shot = طلقة ثلاثية
+power-up-piercing = اختراق
+power-up-shield = درع
+power-up-extra-life = روح إضافية
+power-up-immunity = مناعة
+
+weapon-blaster = مدفع
+weapon-laser = ليزر
+weapon-missiles = صواريخ
+weapon-mines = ألغام
+weapon-shotgun = بندقية
+
+level-banner = المستوى { $level }
+level-countdown.zero = يبدأ الآن...
+level-countdown.one = يبدأ خلال ثانية واحدة...
+level-countdown.two = يبدأ خلال ثانيتين...
+level-countdown.few = يبدأ خلال { $count } ثوانٍ...
+level-countdown.many = يبدأ خلال { $count } ثانية...
+level-countdown.other = يبدأ خلال { $count } ثانية...
+level-get-ready = استعد!
+
+final-score = النتيجة النهائية: { $score }
+level-reached = المستوى الذي وصلت إليه: { $level }
+
+menu-title-main = صخرة الفضاء
+menu-title-pause = إيقاف مؤقت
+menu-title-game-over = انتهت اللعبة
+menu-title-settings = الإعدادات
+menu-title-accessibility = إمكانية الوصول
+menu-title-high-scores = أعلى النتائج
+menu-title-new-high-score = نتيجة قياسية جديدة
+menu-title-credits = الشكر
+
+menu-start = ابدأ اللعب
+menu-settings = الإعدادات
+menu-high-scores = أعلى النتائج
+menu-credits = الشكر
+menu-resume = استئناف
+menu-restart = إعادة البدء
+menu-quit-title = العودة إلى الشاشة الرئيسية
+menu-play-again = العب مجددًا
+menu-main-menu = القائمة الرئيسية
+menu-back = رجوع
+menu-save-score = حفظ النتيجة
+
+setting-volume = مستوى الصوت
+setting-difficulty = الصعوبة
+setting-controls = التحكم
+setting-language = اللغة
+setting-particles = الجسيمات
+setting-accessibility = إمكانية الوصول
+setting-reduced-flash = تقليل الوميض
+setting-colours = الألوان
+setting-high-contrast = تباين عالٍ
+setting-hud-text = حجم النص
+setting-hazard-markers = علامات الخطر
+value-on = تشغيل
+value-off = إيقاف
+value-percent = ٪{ $value }
+
+difficulty-easy = سهل
+difficulty-normal = عادي
+difficulty-hard = صعب
+
+controls-keyboard = لوحة المفاتيح
+controls-mouse = الفأرة
+controls-stick = عصا لمس
+controls-tap = المس للتصويب
+
+palette-standard = قياسي
+palette-deuteranopia = عمى الأخضر
+palette-protanopia = عمى الأحمر
+palette-tritanopia = عمى الأزرق
+
+particles-off = إيقاف
+particles-low = منخفضة
+particles-medium = متوسطة
+particles-high = عالية
+
+title-controls-hint = الأسهم: دوران / دفع   المسافة: إطلاق   P: إيقاف   H: قفزة فضائية
+high-scores-empty = لا توجد نتائج بعد
+high-scores-entry = { $rank }. { $score }   المستوى { $level }
+name-entry-prompt = اكتب اسمك
+name-entry-default = طيار
+high-scores-rejected.zero = لم تُحذف أي نتيجة
+high-scores-rejected.one = حُذفت نتيجة محفوظة غير صالحة
+high-scores-rejected.two = حُذفت نتيجتان محفوظتان غير صالحتين
+high-scores-rejected.few = حُذفت { $count } نتائج محفوظة غير صالحة
+high-scores-rejected.many = حُذفت { $count } نتيجة محفوظة غير صالحة
+high-scores-rejected.other = حُذفت { $count } نتيجة محفوظة غير صالحة
+
+credits-inspiration = مستوحاة من لعبة أتاري الكلاسيكية عام ١٩٧٩
+credits-language = مكتوبة بلغة Rust و WebAssembly
+credits-audio = صوت إجرائي عبر Web Audio API
+credits-graphics = رسوميات متجهة على لوحة HTML
+
+touch-fire = إطلاق
+touch-thrust = دفع
@@ -0,0 +1,118 @@
+# German
+-language-name = Deutsch
+-direction = ltr
+-group-separator = "."
+-min-grouping = 4
+
+hud-score = Punkte: { $score }
+hud-level = Level: { $level }
+hud-lives.one = Leben: { $count }
+hud-lives.other = Leben: { $count }
+hud-shield = Schild
+hud-overheated = ÜBERHITZT
+hud-combo = Kombo ×{ $multiplier }
+popup-points = +{ $points }
+popup-near-miss = KNAPP VORBEI
+
+disruption-disabled = SYSTEME AUSGEFALLEN
+disruption-scrambled = STEUERUNG VERTAUSCHT
+disruption-unstable = SCHIFF INSTABIL
+disruption-gravity = GRAVITATIONSPULS
+disruption-time = ZEITDEHNUNG
+disruption-blackout = STROMAUSFALL
+disruption-magnetic = MAGNETFELD
+disruption-mirror = SPIEGELWELT
+
+boss-titan = DER TITAN
+boss-mothership = DAS MUTTERSCHIFF
+
+power-up-rapid-fire = Schnellfeuer
+power-up-spread-shot = Streuschuss
+power-up-piercing = Durchschlag
+power-up-shield = Schild
+power-up-extra-life = Extraleben
+power-up-immunity = Immunität
+
+weapon-blaster = Blaster
+weapon-laser = Laser
+weapon-missiles = Raketen
+weapon-mines = Minen
+weapon-shotgun = Schrotflinte
+
+level-banner = LEVEL { $level }
+level-countdown.one = Start in { $count } Sekunde...
+level-countdown.other = Start in { $count } Sekunden...
+level-get-ready = Mach dich bereit!
+
+final-score = Endstand: { $score }
+level-reached = Erreichtes Level: { $level }
+
+menu-title-pause = PAUSE
+menu-title-game-over = SPIEL VORBEI
+menu-title-settings = EINSTELLUNGEN
+menu-title-accessibility = BARRIEREFREIHEIT
+menu-title-high-scores = BESTENLISTE
+menu-title-new-high-score = NEUER HIGHSCORE
+menu-title-credits = MITWIRKENDE
+
+menu-start = Spiel starten
+menu-settings = Einstellungen
+menu-high-scores = Bestenliste
+menu-credits = Mitwirkende
+menu-resume = Fortsetzen
+menu-restart = Neu starten
+menu-quit-title = Zum Titelbildschirm
+menu-play-again = Nochmal spielen
+menu-main-menu = Hauptmenü
+menu-back = Zurück
+menu-save-score = Punkte speichern
+
+setting-volume = Lautstärke
+setting-difficulty = Schwierigkeit
+setting-controls = Steuerung
+setting-language = Sprache
+setting-particles = Partikel
+setting-accessibility = Barrierefreiheit
+setting-reduced-flash = Weniger Blitze
+setting-colours = Farben
+setting-high-contrast = Hoher Kontrast
+setting-hud-text = HUD-Text
+setting-hazard-markers = Gefahrensymbole
+value-on = An
+value-off = Aus
+value-percent = { $value } %
+
+difficulty-easy = Leicht
+difficulty-normal = Normal
+difficulty-hard = Schwer
+
+controls-keyboard = Tastatur
+controls-mouse = Maus
+controls-stick = Touch-Stick
+controls-tap = Tippen zum Zielen
+
+palette-standard = Standard
+palette-deuteranopia = Deuteranopie
+palette-protanopia = Protanopie
+palette-tritanopia = Tritanopie
+
+particles-off = Aus
+particles-low = Niedrig
+particles-medium = Mittel
+particles-high = Hoch
+
+title-controls-hint = Pfeile: drehen / Schub   Leertaste: feuern   P: Pause   H: Hyperraum
+high-scores-empty = Noch keine Punktzahlen
+high-scores-entry = { $rank }. { $score }   Level { $level }
+name-entry-prompt = Gib deinen Namen ein
+name-entry-default = PILOT
+high-scores-rejected.one = { $count } ungültiger gespeicherter Eintrag wurde entfernt
+high-scores-rejected.other = { $count } ungültige gespeicherte Einträge wurden entfernt
+
+credits-inspiration = Inspiriert vom Atari-Arcade-Klassiker von 1979
+credits-language = Geschrieben in Rust und WebAssembly
+credits-audio = Prozeduraler Klang über die Web Audio API
+credits-graphics = Vektorgrafik auf HTML-Canvas
+
+touch-fire = FEUER
+touch-thrust = SCHUB
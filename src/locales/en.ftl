@@ -0,0 +1,119 @@
+# English (fallback for every other locale)
+-language-name = English
+-direction = ltr
+-group-separator = ","
+-min-grouping = 4
+
+hud-score = Score: { $score }
+hud-level = Level: { $level }
+hud-lives.one = Lives: { $count }
+hud-lives.other = Lives: { $count }
+hud-shield = Shield
+hud-overheated = OVERHEATED
+hud-combo = Combo ×{ $multiplier }
+popup-points = +{ $points }
+popup-near-miss = NEAR MISS
+
+disruption-disabled = SYSTEMS DISABLED
+disruption-scrambled = CONTROLS SCRAMBLED
+disruption-unstable = SHIP UNSTABLE
+disruption-gravity = GRAVITY PULSE
+disruption-time = TIME DILATION
+disruption-blackout = BLACKOUT
+disruption-magnetic = MAGNETIC FIELD
+disruption-mirror = MIRROR WORLD
+
+boss-titan = THE TITAN
+boss-mothership = THE MOTHERSHIP
+
+power-up-rapid-fire = Rapid Fire
+power-up-spread-shot = Spread Shot
+power-up-piercing = Piercing
+power-up-shield = Shield
+power-up-extra-life = Extra Life
+power-up-immunity = Immunity
+
+weapon-blaster = Blaster
+weapon-laser = Laser
+weapon-missiles = Missiles
+weapon-mines = Mines
+weapon-shotgun = Shotgun
+
+level-banner = LEVEL { $level }
+level-countdown.one = Starting in { $count }...
+level-countdown.other = Starting in { $count }...
+level-get-ready = Get Ready!
+
+final-score = Final Score: { $score }
+level-reached = Level Reached: { $level }
+
+menu-title-main = SPACEROCK
+menu-title-pause = PAUSED
+menu-title-game-over = GAME OVER
+menu-title-settings = SETTINGS
+menu-title-accessibility = ACCESSIBILITY
+menu-title-high-scores = HIGH SCORES
+menu-title-new-high-score = NEW HIGH SCORE
+menu-title-credits = CREDITS
+
+menu-start = Start Game
+menu-settings = Settings
+menu-high-scores = High Scores
+menu-credits = Credits
+menu-resume = Resume
+menu-restart = Restart
+menu-quit-title = Quit to Title
+menu-play-again = Play Again
+menu-main-menu = Main Menu
+menu-back = Back
+menu-save-score = Save Score
+
+setting-volume = Volume
+setting-difficulty = Difficulty
+setting-controls = Controls
+setting-language = Language
+setting-particles = Particles
+setting-accessibility = Accessibility
+setting-reduced-flash = Reduced Flash
+setting-colours = Colours
+setting-high-contrast = High Contrast
+setting-hud-text = HUD Text
+setting-hazard-markers = Hazard Markers
+value-on = On
+value-off = Off
+value-percent = { $value }%
+
+difficulty-easy = Easy
+difficulty-normal = Normal
+difficulty-hard = Hard
+
+controls-keyboard = Keyboard
+controls-mouse = Mouse Aim
+controls-stick = Touch Stick
+controls-tap = Tap to Aim
+
+palette-standard = Standard
+palette-deuteranopia = Deuteranopia
+palette-protanopia = Protanopia
+palette-tritanopia = Tritanopia
+
+particles-off = Off
+particles-low = Low
+particles-medium = Medium
+particles-high = High
+
+title-controls-hint = Arrows: rotate / thrust   Space: fire   P: pause   H: hyperspace
+high-scores-empty = No scores yet
+high-scores-entry = { $rank }. { $score }   Level { $level }
+name-entry-prompt = Type your name
+name-entry-default = PILOT
+high-scores-rejected.one = { $count } saved score failed its check and was removed
+high-scores-rejected.other = { $count } saved scores failed their check and were removed
+
+credits-inspiration = Inspired by Atari's 1979 arcade classic
+credits-language = Written in Rust and WebAssembly
+credits-audio = Procedural audio via the Web Audio API
+credits-graphics = Vector graphics on HTML canvas
+
+touch-fire = FIRE
+touch-thrust = THRUST
@@ -0,0 +1,119 @@
+# Spanish
+-language-name = Español
+-direction = ltr
+-group-separator = "."
+# Spanish only groups numbers of five or more digits
+-min-grouping = 5
+
+hud-score = Puntos: { $score }
+hud-level = Nivel: { $level }
+hud-lives.one = Vidas: { $count }
+hud-lives.other = Vidas: { $count }
+hud-shield = Escudo
+hud-overheated = SOBRECALENTADO
+hud-combo = Combo ×{ $multiplier }
+popup-points = +{ $points }
+popup-near-miss = ¡POR POCO!
+
+disruption-disabled = SISTEMAS DESACTIVADOS
+disruption-scrambled = CONTROLES INVERTIDOS
+disruption-unstable = NAVE INESTABLE
+disruption-gravity = PULSO GRAVITATORIO
+disruption-time = DILATACIÓN TEMPORAL
+disruption-blackout = APAGÓN
+disruption-magnetic = CAMPO MAGNÉTICO
+disruption-mirror = MUNDO ESPEJO
+
+boss-titan = EL TITÁN
+boss-mothership = LA NAVE NODRIZA
+
+power-up-rapid-fire = Fuego rápido
+power-up-spread-shot = Disparo triple
+power-up-piercing = Perforante
+power-up-shield = Escudo
+power-up-extra-life = Vida extra
+power-up-immunity = Inmunidad
+
+weapon-blaster = Bláster
+weapon-laser = Láser
+weapon-missiles = Misiles
+weapon-mines = Minas
+weapon-shotgun = Escopeta
+
+level-banner = NIVEL { $level }
+level-countdown.one = Empieza en { $count } segundo...
+level-countdown.other = Empieza en { $count } segundos...
+level-get-ready = ¡Prepárate!
+
+final-score = Puntuación final: { $score }
+level-reached = Nivel alcanzado: { $level }
+
+menu-title-pause = PAUSA
+menu-title-game-over = FIN DEL JUEGO
+menu-title-settings = AJUSTES
+menu-title-accessibility = ACCESIBILIDAD
+menu-title-high-scores = MEJORES PUNTUACIONES
+menu-title-new-high-score = NUEVA PUNTUACIÓN MÁXIMA
+menu-title-credits = CRÉDITOS
+
+menu-start = Empezar
+menu-settings = Ajustes
+menu-high-scores = Mejores puntuaciones
+menu-credits = Créditos
+menu-resume = Continuar
+menu-restart = Reiniciar
+menu-quit-title = Salir al título
+menu-play-again = Jugar de nuevo
+menu-main-menu = Menú principal
+menu-back = Volver
+menu-save-score = Guardar puntuación
+
+setting-volume = Volumen
+setting-difficulty = Dificultad
+setting-controls = Controles
+setting-language = Idioma
+setting-particles = Partículas
+setting-accessibility = Accesibilidad
+setting-reduced-flash = Menos destellos
+setting-colours = Colores
+setting-high-contrast = Alto contraste
+setting-hud-text = Texto del HUD
+setting-hazard-markers = Marcas de peligro
+value-on = Sí
+value-off = No
+value-percent = { $value } %
+
+difficulty-easy = Fácil
+difficulty-normal = Normal
+difficulty-hard = Difícil
+
+controls-keyboard = Teclado
+controls-mouse = Ratón
+controls-stick = Joystick táctil
+controls-tap = Tocar para apuntar
+
+palette-standard = Estándar
+palette-deuteranopia = Deuteranopía
+palette-protanopia = Protanopía
+palette-tritanopia = Tritanopía
+
+particles-off = No
+particles-low = Bajas
+particles-medium = Medias
+particles-high = Altas
+
+title-controls-hint = Flechas: girar / propulsar   Espacio: disparar   P: pausa   H: hiperespacio
+high-scores-empty = Aún no hay puntuaciones
+high-scores-entry = { $rank }. { $score }   Nivel { $level }
+name-entry-prompt = Escribe tu nombre
+name-entry-default = PILOTO
+high-scores-rejected.one = Se eliminó { $count } puntuación guardada no válida
+high-scores-rejected.other = Se eliminaron { $count } puntuaciones guardadas no válidas
+
+credits-inspiration = Inspirado en el clásico arcade de Atari de 1979
+credits-language = Escrito en Rust y WebAssembly
+credits-audio = Audio procedural con la Web Audio API
+credits-graphics = Gráficos vectoriales en canvas HTML
+
+touch-fire = FUEGO
+touch-thrust = MOTOR
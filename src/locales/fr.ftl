@@ -0,0 +1,119 @@
+# French
+-language-name = Français
+-direction = ltr
+# Narrow no-break space between digit groups
+-group-separator = " "
+-min-grouping = 4
+
+hud-score = Score : { $score }
+hud-level = Niveau : { $level }
+hud-lives.one = Vie : { $count }
+hud-lives.other = Vies : { $count }
+hud-shield = Bouclier
+hud-overheated = SURCHAUFFE
+hud-combo = Combo ×{ $multiplier }
+popup-points = +{ $points }
+popup-near-miss = DE JUSTESSE
+
+disruption-disabled = SYSTÈMES HORS SERVICE
+disruption-scrambled = COMMANDES INVERSÉES
+disruption-unstable = VAISSEAU INSTABLE
+disruption-gravity = IMPULSION GRAVITATIONNELLE
+disruption-time = DILATATION DU TEMPS
+disruption-blackout = PANNE DE LUMIÈRE
+disruption-magnetic = CHAMP MAGNÉTIQUE
+disruption-mirror = MONDE MIROIR
+
+boss-titan = LE TITAN
+boss-mothership = LE VAISSEAU-MÈRE
+
+power-up-rapid-fire = Tir rapide
+power-up-spread-shot = Tir triple
+power-up-piercing = Perforant
+power-up-shield = Bouclier
+power-up-extra-life = Vie bonus
+power-up-immunity = Immunité
+
+weapon-blaster = Blaster
+weapon-laser = Laser
+weapon-missiles = Missiles
+weapon-mines = Mines
+weapon-shotgun = Fusil à pompe
+
+level-banner = NIVEAU { $level }
+level-countdown.one = Début dans { $count } seconde...
+level-countdown.other = Début dans { $count } secondes...
+level-get-ready = Préparez-vous !
+
+final-score = Score final : { $score }
+level-reached = Niveau atteint : { $level }
+
+menu-title-pause = PAUSE
+menu-title-game-over = PARTIE TERMINÉE
+menu-title-settings = PARAMÈTRES
+menu-title-accessibility = ACCESSIBILITÉ
+menu-title-high-scores = MEILLEURS SCORES
+menu-title-new-high-score = NOUVEAU RECORD
+menu-title-credits = CRÉDITS
+
+menu-start = Jouer
+menu-settings = Paramètres
+menu-high-scores = Meilleurs scores
+menu-credits = Crédits
+menu-resume = Reprendre
+menu-restart = Recommencer
+menu-quit-title = Retour au titre
+menu-play-again = Rejouer
+menu-main-menu = Menu principal
+menu-back = Retour
+menu-save-score = Enregistrer le score
+
+setting-volume = Volume
+setting-difficulty = Difficulté
+setting-controls = Commandes
+setting-language = Langue
+setting-particles = Particules
+setting-accessibility = Accessibilité
+setting-reduced-flash = Moins de flashs
+setting-colours = Couleurs
+setting-high-contrast = Contraste élevé
+setting-hud-text = Texte du HUD
+setting-hazard-markers = Marqueurs de danger
+value-on = Oui
+value-off = Non
+value-percent = { $value } %
+
+difficulty-easy = Facile
+difficulty-normal = Normal
+difficulty-hard = Difficile
+
+controls-keyboard = Clavier
+controls-mouse = Souris
+controls-stick = Joystick tactile
+controls-tap = Toucher pour viser
+
+palette-standard = Standard
+palette-deuteranopia = Deutéranopie
+palette-protanopia = Protanopie
+palette-tritanopia = Tritanopie
+
+particles-off = Aucune
+particles-low = Basses
+particles-medium = Moyennes
+particles-high = Hautes
+
+title-controls-hint = Flèches : tourner / propulser   Espace : tirer   P : pause   H : hyperespace
+high-scores-empty = Aucun score pour l'instant
+high-scores-entry = { $rank }. { $score }   Niveau { $level }
+name-entry-prompt = Tapez votre nom
+name-entry-default = PILOTE
+high-scores-rejected.one = { $count } score enregistré invalide a été supprimé
+high-scores-rejected.other = { $count } scores enregistrés invalides ont été supprimés
+
+credits-inspiration = Inspiré du classique d'arcade d'Atari de 1979
+credits-language = Écrit en Rust et WebAssembly
+credits-audio = Audio procédural via la Web Audio API
+credits-graphics = Graphismes vectoriels sur canvas HTML
+
+touch-fire = TIR
+touch-thrust = POUSSÉE
@@ -0,0 +1,103 @@
+use crate::accessibility::Theme;
+
+// Declared in ALL_MATERIALS order, which the theme's colours follow
+#[derive(Clone, Copy, PartialEq)]
+pub enum Material {
+    Rock,
+    // Takes several hits, cracking a little more with each one
+    Metallic,
+    // Shatters into three fast fragments
+    Ice,
+    // Blows up, taking nearby rocks with it
+    Explosive,
+    // Bullets glance off it, cracking it on the way
+    Crystal,
+    // Small, heavy and never splits
+    Dense,
+}
+
+impl Material {
+    pub fn spawn_size(&self) -> f64 {
+        match self {
+            Material::Dense => 20.0,
+            _ => 40.0,
+        }
+    }
+
+    // Hits needed to break a rock of this material
+    pub fn hit_points(&self, size: f64) -> i32 {
+        match self {
+            Material::Metallic if size >= 40.0 => 3,
+            Material::Metallic | Material::Crystal => 2,
+            _ => 1,
+        }
+    }
+
+    // Mass per unit of size², for collisions and shock waves
+    pub fn density(&self) -> f64 {
+        match self {
+            Material::Metallic => 2.0,
+            Material::Ice => 0.6,
+            Material::Dense => 3.0,
+            _ => 1.0,
+        }
+    }
+
+    // Number of fragments left behind; the three-way split setting can raise
+    // plain rock to three
+    pub fn fragments(&self) -> usize {
+        match self {
+            Material::Ice => 3,
+            Material::Explosive | Material::Dense => 0,
+            _ => 2,
+        }
+    }
+
+    pub fn fragment_speed(&self) -> f64 {
+        match self {
+            Material::Ice => 1.8,
+            _ => 1.0,
+        }
+    }
+
+    pub fn score_factor(&self) -> i32 {
+        match self {
+            Material::Rock | Material::Ice => 1,
+            Material::Explosive | Material::Dense => 2,
+            Material::Metallic | Material::Crystal => 3,
+        }
+    }
+
+    pub fn color(&self, theme: &Theme) -> &'static str {
+        theme.material_colors[*self as usize]
+    }
+
+    // Played when a hit doesn't break the rock
+    pub fn hit_sound(&self) -> Option<&'static str> {
+        match self {
+            Material::Metallic => Some("metal-hit-sound"),
+            Material::Crystal => Some("crystal-ricochet-sound"),
+            _ => None,
+        }
+    }
+
+    pub fn break_sound(&self) -> Option<&'static str> {
+        match self {
+            Material::Rock => None,
+            Material::Metallic => Some("metal-break-sound"),
+            Material::Ice => Some("ice-shatter-sound"),
+            Material::Explosive => Some("mine-explosion-sound"),
+            Material::Crystal => Some("crystal-shatter-sound"),
+            Material::Dense => Some("dense-break-sound"),
+        }
+    }
+}
+
+pub const ALL_MATERIALS: [Material; 6] = [
+    Material::Rock,
+    Material::Metallic,
+    Material::Ice,
+    Material::Explosive,
+    Material::Crystal,
+    Material::Dense,
+];
@@ -0,0 +1,247 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::accessibility::Theme;
+use crate::i18n::Localizer;
+use crate::settings::Settings;
+use crate::Vector;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MenuPage {
+    Main,
+    Pause,
+    GameOver,
+    Settings,
+    Accessibility,
+    HighScores,
+    // Asks for a name after a game over that made the high score table
+    NameEntry,
+    Credits,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SettingKey {
+    Volume,
+    Difficulty,
+    ControlScheme,
+    Language,
+    Particles,
+    ReducedFlash,
+    Palette,
+    HighContrast,
+    HudScale,
+    HazardMarkers,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MenuAction {
+    StartGame,
+    Resume,
+    QuitToTitle,
+    Open(MenuPage),
+    Back,
+    SaveScore,
+    Adjust(SettingKey),
+}
+
+// Directional and confirm/cancel input shared by keyboard, gamepad and pointer
+#[derive(Clone, Copy, PartialEq)]
+pub enum MenuInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Back,
+}
+
+pub struct MenuItem {
+    pub label: String,
+    pub value: Option<String>,
+    pub action: MenuAction,
+}
+
+impl MenuItem {
+    fn action(i18n: &Localizer, label_key: &str, action: MenuAction) -> Self {
+        MenuItem { label: i18n.text(label_key), value: None, action }
+    }
+
+    fn setting(i18n: &Localizer, label_key: &str, value: String, key: SettingKey) -> Self {
+        MenuItem { label: i18n.text(label_key), value: Some(value), action: MenuAction::Adjust(key) }
+    }
+}
+
+const ITEM_TOP: f64 = 250.0;
+const ITEM_SPACING: f64 = 44.0;
+const ITEM_WIDTH: f64 = 380.0;
+const ITEM_HEIGHT: f64 = 36.0;
+const LINE_SPACING: f64 = 26.0;
+
+pub struct Menu {
+    stack: Vec<MenuPage>,
+    pub selected: usize,
+}
+
+impl Menu {
+    pub fn new() -> Self {
+        Menu { stack: vec![MenuPage::Main], selected: 0 }
+    }
+
+    pub fn open_root(&mut self, page: MenuPage) {
+        self.stack = vec![page];
+        self.selected = 0;
+    }
+
+    pub fn page(&self) -> MenuPage {
+        *self.stack.last().unwrap_or(&MenuPage::Main)
+    }
+
+    pub fn push(&mut self, page: MenuPage) {
+        self.stack.push(page);
+        self.selected = 0;
+    }
+
+    // Returns false when already on the root page
+    pub fn pop(&mut self) -> bool {
+        if self.stack.len() <= 1 {
+            return false;
+        }
+        self.stack.pop();
+        self.selected = 0;
+        true
+    }
+
+    pub fn move_selection(&mut self, step: i32, item_count: usize) {
+        if item_count == 0 {
+            return;
+        }
+        self.selected = (self.selected as i32 + step).rem_euclid(item_count as i32) as usize;
+    }
+
+    pub fn item_at(&self, point: &Vector, item_count: usize, line_count: usize) -> Option<usize> {
+        (0..item_count).find(|index| {
+            let (x, y, width, height) = item_rect(*index, line_count);
+            point.x >= x && point.x <= x + width && point.y >= y && point.y <= y + height
+        })
+    }
+
+    pub fn title_key(&self) -> &'static str {
+        match self.page() {
+            MenuPage::Main => "menu-title-main",
+            MenuPage::Pause => "menu-title-pause",
+            MenuPage::GameOver => "menu-title-game-over",
+            MenuPage::Settings => "menu-title-settings",
+            MenuPage::Accessibility => "menu-title-accessibility",
+            MenuPage::HighScores => "menu-title-high-scores",
+            MenuPage::NameEntry => "menu-title-new-high-score",
+            MenuPage::Credits => "menu-title-credits",
+        }
+    }
+
+    pub fn items(&self, settings: &Settings, i18n: &Localizer) -> Vec<MenuItem> {
+        let action = |label_key, action| MenuItem::action(i18n, label_key, action);
+        let setting = |label_key, value, key| MenuItem::setting(i18n, label_key, value, key);
+        match self.page() {
+            MenuPage::Main => vec![
+                action("menu-start", MenuAction::StartGame),
+                action("menu-settings", MenuAction::Open(MenuPage::Settings)),
+                action("menu-high-scores", MenuAction::Open(MenuPage::HighScores)),
+                action("menu-credits", MenuAction::Open(MenuPage::Credits)),
+            ],
+            MenuPage::Pause => vec![
+                action("menu-resume", MenuAction::Resume),
+                action("menu-restart", MenuAction::StartGame),
+                action("menu-settings", MenuAction::Open(MenuPage::Settings)),
+                action("menu-quit-title", MenuAction::QuitToTitle),
+            ],
+            MenuPage::GameOver => vec![
+                action("menu-play-again", MenuAction::StartGame),
+                action("menu-high-scores", MenuAction::Open(MenuPage::HighScores)),
+                action("menu-main-menu", MenuAction::QuitToTitle),
+            ],
+            MenuPage::Settings => vec![
+                setting("setting-volume", percent(i18n, settings.volume), SettingKey::Volume),
+                setting("setting-difficulty", i18n.text(settings.difficulty.label_key()), SettingKey::Difficulty),
+                setting("setting-controls", i18n.text(settings.control_scheme.label_key()), SettingKey::ControlScheme),
+                setting("setting-language", i18n.text("-language-name"), SettingKey::Language),
+                setting("setting-particles", i18n.text(settings.particles.label_key()), SettingKey::Particles),
+                action("setting-accessibility", MenuAction::Open(MenuPage::Accessibility)),
+                action("menu-back", MenuAction::Back),
+            ],
+            MenuPage::Accessibility => {
+                let accessibility = &settings.accessibility;
+                vec![
+                    setting("setting-reduced-flash", on_off(i18n, accessibility.reduced_flash), SettingKey::ReducedFlash),
+                    setting("setting-colours", i18n.text(accessibility.palette.label_key()), SettingKey::Palette),
+                    setting("setting-high-contrast", on_off(i18n, accessibility.high_contrast), SettingKey::HighContrast),
+                    setting("setting-hud-text", percent(i18n, accessibility.hud_scale), SettingKey::HudScale),
+                    setting("setting-hazard-markers", on_off(i18n, accessibility.hazard_markers), SettingKey::HazardMarkers),
+                    action("menu-back", MenuAction::Back),
+                ]
+            }
+            MenuPage::NameEntry => vec![action("menu-save-score", MenuAction::SaveScore)],
+            MenuPage::HighScores | MenuPage::Credits => vec![action("menu-back", MenuAction::Back)],
+        }
+    }
+
+    // `lines` is extra text shown above the items, such as scores or credits
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, items: &[MenuItem], lines: &[String], theme: &Theme, i18n: &Localizer) {
+        ctx.save();
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+
+        ctx.set_fill_style_str("white");
+        ctx.set_font("48px Arial");
+        ctx.fill_text(&i18n.text(self.title_key()), 400.0, 120.0).unwrap();
+
+        ctx.set_font("20px Arial");
+        for (index, line) in lines.iter().enumerate() {
+            ctx.fill_text(line, 400.0, 170.0 + index as f64 * LINE_SPACING).unwrap();
+        }
+
+        ctx.set_font("24px Arial");
+        for (index, item) in items.iter().enumerate() {
+            let (x, y, width, height) = item_rect(index, lines.len());
+            let center_y = y + height / 2.0;
+
+            if index == self.selected {
+                ctx.set_stroke_style_str(theme.highlight);
+                ctx.set_line_width(2.0 * theme.line_scale);
+                ctx.stroke_rect(x, y, width, height);
+
+                // Chevrons either side of the selection, in the ship's line style
+                for side in [-1.0, 1.0] {
+                    let tip_x = 400.0 + side * (width / 2.0 + 14.0);
+                    ctx.begin_path();
+                    ctx.move_to(tip_x + side * 10.0, center_y - 8.0);
+                    ctx.line_to(tip_x, center_y);
+                    ctx.line_to(tip_x + side * 10.0, center_y + 8.0);
+                    ctx.stroke();
+                }
+            }
+
+            ctx.set_fill_style_str(if index == self.selected { theme.highlight } else { "white" });
+            let text = match &item.value {
+                Some(value) => format!("{}: < {} >", item.label, value),
+                None => item.label.clone(),
+            };
+            ctx.fill_text(&text, 400.0, center_y).unwrap();
+        }
+
+        ctx.restore();
+    }
+}
+
+// Info pages push their items below the text block
+fn item_rect(index: usize, line_count: usize) -> (f64, f64, f64, f64) {
+    let offset = line_count.saturating_sub(3) as f64 * LINE_SPACING;
+    let y = ITEM_TOP + offset + index as f64 * ITEM_SPACING;
+    (400.0 - ITEM_WIDTH / 2.0, y, ITEM_WIDTH, ITEM_HEIGHT)
+}
+
+fn on_off(i18n: &Localizer, value: bool) -> String {
+    i18n.text(if value { "value-on" } else { "value-off" })
+}
+
+fn percent(i18n: &Localizer, fraction: f64) -> String {
+    i18n.format("value-percent", &[("value", (fraction * 100.0).round() as i64)])
+}
@@ -0,0 +1,312 @@
+use js_sys::Math;
+use std::f64::consts::PI;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::accessibility::Theme;
+use crate::{Vector, WORLD_HEIGHT, WORLD_WIDTH};
+
+type Rgb = (u8, u8, u8);
+
+const WHITE: Rgb = (255, 255, 255);
+const GREY: Rgb = (90, 90, 90);
+const FLAME: Rgb = (255, 200, 60);
+const EMBER: Rgb = (200, 40, 0);
+const SPARK: Rgb = (255, 240, 160);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ParticleQuality {
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+impl ParticleQuality {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParticleQuality::Off => "off",
+            ParticleQuality::Low => "low",
+            ParticleQuality::Medium => "medium",
+            ParticleQuality::High => "high",
+        }
+    }
+
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            ParticleQuality::Off => "particles-off",
+            ParticleQuality::Low => "particles-low",
+            ParticleQuality::Medium => "particles-medium",
+            ParticleQuality::High => "particles-high",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ParticleQuality> {
+        match name {
+            "off" => Some(ParticleQuality::Off),
+            "low" => Some(ParticleQuality::Low),
+            "medium" => Some(ParticleQuality::Medium),
+            "high" => Some(ParticleQuality::High),
+            _ => None,
+        }
+    }
+
+    pub fn cycled(&self, step: i32) -> ParticleQuality {
+        const ALL: [ParticleQuality; 4] =
+            [ParticleQuality::Off, ParticleQuality::Low, ParticleQuality::Medium, ParticleQuality::High];
+        crate::settings::cycle(&ALL, *self, step)
+    }
+
+    // Size of the particle pool
+    fn max_particles(&self) -> usize {
+        match self {
+            ParticleQuality::Off => 0,
+            ParticleQuality::Low => 150,
+            ParticleQuality::Medium => 400,
+            ParticleQuality::High => 1000,
+        }
+    }
+
+    // Share of each emitter's full particle count
+    fn density(&self) -> f64 {
+        match self {
+            ParticleQuality::Off => 0.0,
+            ParticleQuality::Low => 0.35,
+            ParticleQuality::Medium => 0.7,
+            ParticleQuality::High => 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Shape {
+    Dot(f64),
+    // A line segment of the given length, tumbling as it flies
+    Segment(f64),
+}
+
+#[derive(Clone)]
+struct Particle {
+    alive: bool,
+    position: Vector,
+    velocity: Vector,
+    // Fraction of velocity kept each frame
+    drag: f64,
+    angle: f64,
+    spin: f64,
+    age: i32,
+    lifetime: i32,
+    from: Rgb,
+    to: Rgb,
+    shape: Shape,
+}
+
+impl Particle {
+    fn dead() -> Self {
+        Particle {
+            alive: false,
+            position: Vector { x: 0.0, y: 0.0 },
+            velocity: Vector { x: 0.0, y: 0.0 },
+            drag: 1.0,
+            angle: 0.0,
+            spin: 0.0,
+            age: 0,
+            lifetime: 1,
+            from: WHITE,
+            to: WHITE,
+            shape: Shape::Dot(1.0),
+        }
+    }
+
+    // Colour and opacity both fade over the particle's life
+    fn color(&self) -> String {
+        let t = self.age as f64 / self.lifetime as f64;
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        format!(
+            "rgba({}, {}, {}, {:.2})",
+            mix(self.from.0, self.to.0),
+            mix(self.from.1, self.to.1),
+            mix(self.from.2, self.to.2),
+            1.0 - t
+        )
+    }
+}
+
+// A fixed pool of particles. New particles reuse dead slots, and once the
+// pool is full the oldest live particle is recycled.
+pub struct ParticleSystem {
+    pool: Vec<Particle>,
+    next: usize,
+    quality: ParticleQuality,
+}
+
+impl ParticleSystem {
+    pub fn new(quality: ParticleQuality) -> Self {
+        ParticleSystem { pool: vec![Particle::dead(); quality.max_particles()], next: 0, quality }
+    }
+
+    pub fn set_quality(&mut self, quality: ParticleQuality) {
+        *self = ParticleSystem::new(quality);
+    }
+
+    pub fn clear(&mut self) {
+        for particle in &mut self.pool {
+            particle.alive = false;
+        }
+    }
+
+    fn count(&self, full: f64) -> usize {
+        (full * self.quality.density()).round() as usize
+    }
+
+    fn spawn(&mut self, particle: Particle) {
+        if self.pool.is_empty() {
+            return;
+        }
+        let start = self.next;
+        let slot = (0..self.pool.len())
+            .map(|offset| (start + offset) % self.pool.len())
+            .find(|&index| !self.pool[index].alive)
+            .unwrap_or(start);
+        self.pool[slot] = particle;
+        self.next = (slot + 1) % self.pool.len();
+    }
+
+    fn burst(&mut self, origin: &Vector, count: usize, speed: (f64, f64), lifetime: (i32, i32), colors: (Rgb, Rgb), shape: Shape) {
+        for _ in 0..count {
+            let angle = Math::random() * 2.0 * PI;
+            let speed = speed.0 + Math::random() * (speed.1 - speed.0);
+            self.spawn(Particle {
+                alive: true,
+                position: origin.clone(),
+                velocity: Vector { x: speed * angle.sin(), y: -speed * angle.cos() },
+                drag: 0.96,
+                angle: Math::random() * 2.0 * PI,
+                spin: (Math::random() - 0.5) * 0.3,
+                age: 0,
+                lifetime: lifetime.0 + (Math::random() * (lifetime.1 - lifetime.0) as f64) as i32,
+                from: colors.0,
+                to: colors.1,
+                shape,
+            });
+        }
+    }
+
+    // Bigger rocks throw out more, faster dust and chips
+    pub fn asteroid_explosion(&mut self, origin: &Vector, size: f64) {
+        let count = self.count(size * 0.6);
+        self.burst(origin, count, (0.5, size / 12.0 + 1.0), (25, 50), (WHITE, GREY), Shape::Dot(1.5));
+        let chips = self.count(size / 8.0);
+        self.burst(origin, chips, (0.3, 1.5), (40, 70), (WHITE, GREY), Shape::Segment(size / 8.0));
+    }
+
+    // The hull breaks into its three edges, which drift apart and tumble
+    pub fn ship_debris(&mut self, origin: &Vector, rotation: f64, velocity: &Vector) {
+        if self.quality == ParticleQuality::Off {
+            return;
+        }
+        let hull = [(0.0, -20.0), (10.0, 20.0), (-10.0, 20.0)];
+        for edge in 0..hull.len() {
+            let (ax, ay) = hull[edge];
+            let (bx, by) = hull[(edge + 1) % hull.len()];
+            let (mid_x, mid_y) = ((ax + bx) / 2.0, (ay + by) / 2.0);
+            let (sin, cos) = rotation.sin_cos();
+            let offset = Vector { x: mid_x * cos - mid_y * sin, y: mid_x * sin + mid_y * cos };
+            let length = ((bx - ax) * (bx - ax) + (by - ay) * (by - ay)).sqrt();
+            self.spawn(Particle {
+                alive: true,
+                position: Vector { x: origin.x + offset.x, y: origin.y + offset.y },
+                velocity: Vector { x: velocity.x + offset.x * 0.05, y: velocity.y + offset.y * 0.05 },
+                drag: 0.99,
+                angle: rotation + (by - ay).atan2(bx - ax),
+                spin: (Math::random() - 0.5) * 0.2,
+                age: 0,
+                lifetime: 120,
+                from: WHITE,
+                to: GREY,
+                shape: Shape::Segment(length),
+            });
+        }
+        let count = self.count(20.0);
+        self.burst(origin, count, (0.5, 3.0), (20, 40), (FLAME, EMBER), Shape::Dot(1.5));
+    }
+
+    // Exhaust streams out of the back of the ship, opposite `direction`
+    pub fn exhaust(&mut self, origin: &Vector, direction: f64, velocity: &Vector) {
+        let count = self.count(2.0).max(usize::from(self.quality != ParticleQuality::Off));
+        for _ in 0..count {
+            let spread = direction + PI + (Math::random() - 0.5) * 0.5;
+            let speed = 1.5 + Math::random() * 1.5;
+            self.spawn(Particle {
+                alive: true,
+                position: Vector { x: origin.x + 18.0 * spread.sin(), y: origin.y - 18.0 * spread.cos() },
+                velocity: Vector { x: velocity.x + speed * spread.sin(), y: velocity.y - speed * spread.cos() },
+                drag: 0.94,
+                angle: 0.0,
+                spin: 0.0,
+                age: 0,
+                lifetime: 12 + (Math::random() * 10.0) as i32,
+                from: FLAME,
+                to: EMBER,
+                shape: Shape::Dot(1.5),
+            });
+        }
+    }
+
+    pub fn impact_sparks(&mut self, origin: &Vector) {
+        let count = self.count(6.0);
+        self.burst(origin, count, (1.0, 3.0), (8, 16), (SPARK, FLAME), Shape::Dot(1.0));
+    }
+
+    // Crackling arcs in the palette's special colour
+    pub fn electric_burst(&mut self, origin: &Vector, theme: &Theme) {
+        let color = parse_hex(theme.special);
+        let count = self.count(24.0);
+        self.burst(origin, count, (2.0, 5.0), (15, 35), (WHITE, color), Shape::Segment(6.0));
+    }
+
+    pub fn update(&mut self) {
+        for particle in self.pool.iter_mut().filter(|particle| particle.alive) {
+            particle.age += 1;
+            if particle.age >= particle.lifetime {
+                particle.alive = false;
+                continue;
+            }
+            particle.velocity.x *= particle.drag;
+            particle.velocity.y *= particle.drag;
+            particle.position.x = (particle.position.x + particle.velocity.x).rem_euclid(WORLD_WIDTH);
+            particle.position.y = (particle.position.y + particle.velocity.y).rem_euclid(WORLD_HEIGHT);
+            particle.angle += particle.spin;
+        }
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+        ctx.save();
+        ctx.set_line_width(theme.line_scale);
+        for particle in self.pool.iter().filter(|particle| particle.alive) {
+            let color = particle.color();
+            match particle.shape {
+                Shape::Dot(radius) => {
+                    ctx.set_fill_style_str(&color);
+                    ctx.begin_path();
+                    ctx.arc(particle.position.x, particle.position.y, radius, 0.0, 2.0 * PI).unwrap();
+                    ctx.fill();
+                }
+                Shape::Segment(length) => {
+                    let (dx, dy) = (particle.angle.cos() * length / 2.0, particle.angle.sin() * length / 2.0);
+                    ctx.set_stroke_style_str(&color);
+                    ctx.begin_path();
+                    ctx.move_to(particle.position.x - dx, particle.position.y - dy);
+                    ctx.line_to(particle.position.x + dx, particle.position.y + dy);
+                    ctx.stroke();
+                }
+            }
+        }
+        ctx.restore();
+    }
+}
+
+// Palette colours are "#rrggbb"
+fn parse_hex(color: &str) -> Rgb {
+    let channel = |range| u8::from_str_radix(color.get(range).unwrap_or("ff"), 16).unwrap_or(255);
+    (channel(1..3), channel(3..5), channel(5..7))
+}
@@ -0,0 +1,232 @@
+use std::f64::consts::PI;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::accessibility::Theme;
+use crate::rng;
+use crate::{Vector, WORLD_HEIGHT, WORLD_WIDTH};
+
+pub const PICKUP_RADIUS: f64 = 12.0;
+const PICKUP_LIFETIME: i32 = 600; // 10 seconds at 60 FPS
+const EXPIRY_WARNING: i32 = 120;
+pub const RAPID_FIRE_REPEAT_FRAMES: i32 = 5;
+pub const SPREAD_ANGLE: f64 = 0.2;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PowerUpKind {
+    RapidFire,
+    SpreadShot,
+    Piercing,
+    Shield,
+    ExtraLife,
+    Immunity,
+}
+
+impl PowerUpKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerUpKind::RapidFire => "rapid_fire",
+            PowerUpKind::SpreadShot => "spread_shot",
+            PowerUpKind::Piercing => "piercing",
+            PowerUpKind::Shield => "shield",
+            PowerUpKind::ExtraLife => "extra_life",
+            PowerUpKind::Immunity => "immunity",
+        }
+    }
+
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            PowerUpKind::RapidFire => "power-up-rapid-fire",
+            PowerUpKind::SpreadShot => "power-up-spread-shot",
+            PowerUpKind::Piercing => "power-up-piercing",
+            PowerUpKind::Shield => "power-up-shield",
+            PowerUpKind::ExtraLife => "power-up-extra-life",
+            PowerUpKind::Immunity => "power-up-immunity",
+        }
+    }
+
+    // Letter drawn inside the pickup so kinds are told apart by shape
+    fn symbol(&self) -> &'static str {
+        match self {
+            PowerUpKind::RapidFire => "R",
+            PowerUpKind::SpreadShot => "3",
+            PowerUpKind::Piercing => "P",
+            PowerUpKind::Shield => "S",
+            PowerUpKind::ExtraLife => "+",
+            PowerUpKind::Immunity => "I",
+        }
+    }
+
+    // Frames the effect lasts; instant effects have none
+    pub fn duration(&self) -> i32 {
+        match self {
+            PowerUpKind::RapidFire | PowerUpKind::SpreadShot | PowerUpKind::Piercing => 600,
+            PowerUpKind::Shield => 420,
+            PowerUpKind::Immunity => 900,
+            PowerUpKind::ExtraLife => 0,
+        }
+    }
+
+    fn weight(&self) -> f64 {
+        match self {
+            PowerUpKind::ExtraLife => 0.5,
+            _ => 1.0,
+        }
+    }
+
+    pub fn random() -> PowerUpKind {
+        const ALL: [PowerUpKind; 6] = [
+            PowerUpKind::RapidFire,
+            PowerUpKind::SpreadShot,
+            PowerUpKind::Piercing,
+            PowerUpKind::Shield,
+            PowerUpKind::ExtraLife,
+            PowerUpKind::Immunity,
+        ];
+        let total: f64 = ALL.iter().map(|kind| kind.weight()).sum();
+        let mut roll = rng::random() * total;
+        for kind in ALL {
+            roll -= kind.weight();
+            if roll < 0.0 {
+                return kind;
+            }
+        }
+        PowerUpKind::RapidFire
+    }
+}
+
+pub struct PowerUpSettings {
+    // Drop chance per destroyed rock, indexed by level; the last entry
+    // applies to every later level
+    pub drop_rates: Vec<f64>,
+}
+
+impl PowerUpSettings {
+    pub fn new() -> Self {
+        PowerUpSettings { drop_rates: vec![0.05, 0.06, 0.07, 0.08, 0.1] }
+    }
+
+    pub fn drop_chance(&self, level: i32) -> f64 {
+        let index = ((level - 1).max(0) as usize).min(self.drop_rates.len().saturating_sub(1));
+        self.drop_rates.get(index).copied().unwrap_or(0.0)
+    }
+}
+
+pub struct PowerUp {
+    pub kind: PowerUpKind,
+    pub position: Vector,
+    velocity: Vector,
+    lifetime: i32,
+}
+
+impl PowerUp {
+    pub fn new(kind: PowerUpKind, x: f64, y: f64) -> Self {
+        let angle = rng::random() * 2.0 * PI;
+        PowerUp {
+            kind,
+            position: Vector { x, y },
+            velocity: Vector { x: 0.5 * angle.sin(), y: -0.5 * angle.cos() },
+            lifetime: PICKUP_LIFETIME,
+        }
+    }
+
+    pub fn update(&mut self) -> bool {
+        self.position.x = (self.position.x + self.velocity.x).rem_euclid(WORLD_WIDTH);
+        self.position.y = (self.position.y + self.velocity.y).rem_euclid(WORLD_HEIGHT);
+        self.lifetime -= 1;
+        self.lifetime > 0
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+        // Blink before disappearing, or fade steadily with reduced flash
+        let expiring = self.lifetime < EXPIRY_WARNING;
+        if expiring && !theme.reduced_flash && self.lifetime % 20 < 10 {
+            return;
+        }
+
+        ctx.save();
+        if expiring && theme.reduced_flash {
+            ctx.set_global_alpha(0.3 + 0.7 * self.lifetime as f64 / EXPIRY_WARNING as f64);
+        }
+        ctx.translate(self.position.x, self.position.y).unwrap();
+        ctx.set_stroke_style_str(theme.highlight);
+        ctx.set_fill_style_str(theme.highlight);
+        ctx.set_line_width(1.5 * theme.line_scale);
+
+        // Diamond outline with the kind's letter inside
+        ctx.begin_path();
+        ctx.move_to(0.0, -PICKUP_RADIUS);
+        ctx.line_to(PICKUP_RADIUS, 0.0);
+        ctx.line_to(0.0, PICKUP_RADIUS);
+        ctx.line_to(-PICKUP_RADIUS, 0.0);
+        ctx.close_path();
+        ctx.stroke();
+
+        ctx.set_font("bold 12px Arial");
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+        ctx.fill_text(self.kind.symbol(), 0.0, 1.0).unwrap();
+        ctx.restore();
+    }
+}
+
+pub struct ActivePowerUp {
+    pub kind: PowerUpKind,
+    pub remaining: i32,
+}
+
+impl ActivePowerUp {
+    pub fn fraction_left(&self) -> f64 {
+        self.remaining as f64 / self.kind.duration().max(1) as f64
+    }
+}
+
+// Timed effects currently on the ship
+pub struct PowerUpEffects {
+    pub active: Vec<ActivePowerUp>,
+    rapid_fire_timer: i32,
+}
+
+impl PowerUpEffects {
+    pub fn new() -> Self {
+        PowerUpEffects { active: Vec::new(), rapid_fire_timer: 0 }
+    }
+
+    pub fn has(&self, kind: PowerUpKind) -> bool {
+        self.active.iter().any(|effect| effect.kind == kind)
+    }
+
+    // Collecting an effect that is already running restarts its timer
+    pub fn grant(&mut self, kind: PowerUpKind) {
+        if kind.duration() == 0 {
+            return;
+        }
+        match self.active.iter_mut().find(|effect| effect.kind == kind) {
+            Some(effect) => effect.remaining = kind.duration(),
+            None => self.active.push(ActivePowerUp { kind, remaining: kind.duration() }),
+        }
+    }
+
+    // Returns the effects that ran out this frame
+    pub fn tick(&mut self) -> Vec<PowerUpKind> {
+        for effect in &mut self.active {
+            effect.remaining -= 1;
+        }
+        let expired = self.active.iter().filter(|effect| effect.remaining <= 0).map(|effect| effect.kind).collect();
+        self.active.retain(|effect| effect.remaining > 0);
+        expired
+    }
+
+    // Auto-fire for a held key while rapid fire is active
+    pub fn rapid_fire_ready(&mut self, held: bool) -> bool {
+        if !held || !self.has(PowerUpKind::RapidFire) {
+            self.rapid_fire_timer = 0;
+            return false;
+        }
+        let ready = self.rapid_fire_timer == 0;
+        if ready {
+            self.rapid_fire_timer = RAPID_FIRE_REPEAT_FRAMES;
+        }
+        self.rapid_fire_timer -= 1;
+        ready
+    }
+}
@@ -0,0 +1,51 @@
+use std::cell::Cell;
+
+// Gameplay randomness (spawns, splits, saucers, drops, disruptions) comes
+// from here, so a run can be replayed from its seed. Purely cosmetic
+// randomness, like particles, stays on Math::random so that it doesn't
+// depend on the particle quality setting and can't shift the sequence.
+thread_local! {
+    static STATE: Cell<u64> = const { Cell::new(0) };
+}
+
+pub fn seed(seed: u32) {
+    STATE.with(|state| state.set(seed as u64));
+}
+
+// SplitMix64, as a uniform float in [0, 1) like Math::random
+pub fn random() -> f64 {
+    let mut z = STATE.with(|state| {
+        let next = state.get().wrapping_add(0x9e37_79b9_7f4a_7c15);
+        state.set(next);
+        next
+    });
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence(seed_value: u32) -> Vec<f64> {
+        seed(seed_value);
+        (0..100).map(|_| random()).collect()
+    }
+
+    #[test]
+    fn same_seed_replays_the_same_sequence() {
+        assert_eq!(sequence(42), sequence(42));
+        assert_ne!(sequence(42), sequence(43));
+        assert_ne!(sequence(0), sequence(u32::MAX));
+    }
+
+    #[test]
+    fn values_stay_in_the_unit_interval() {
+        let values = sequence(2024);
+        assert!(values.iter().all(|value| (0.0..1.0).contains(value)));
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        assert!((0.35..0.65).contains(&mean));
+    }
+}
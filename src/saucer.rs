@@ -0,0 +1,175 @@
+use std::f64::consts::PI;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::accessibility::Theme;
+use crate::rng;
+use crate::{wrap_delta, Vector, WORLD_HEIGHT, WORLD_WIDTH};
+
+pub const ENEMY_BULLET_SPEED: f64 = 6.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SaucerKind {
+    Large,
+    Small,
+}
+
+impl SaucerKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SaucerKind::Large => "large",
+            SaucerKind::Small => "small",
+        }
+    }
+
+    pub fn radius(&self) -> f64 {
+        match self {
+            SaucerKind::Large => 20.0,
+            SaucerKind::Small => 10.0,
+        }
+    }
+
+    fn speed(&self) -> f64 {
+        match self {
+            SaucerKind::Large => 1.5,
+            SaucerKind::Small => 2.5,
+        }
+    }
+
+    pub fn score(&self) -> i32 {
+        match self {
+            SaucerKind::Large => 200,
+            SaucerKind::Small => 1000,
+        }
+    }
+
+    fn fire_interval(&self) -> i32 {
+        match self {
+            SaucerKind::Large => 90,
+            SaucerKind::Small => 60,
+        }
+    }
+
+    // Engine warble, replayed while the saucer is on screen
+    pub fn sound_id(&self) -> &'static str {
+        match self {
+            SaucerKind::Large => "saucer-large-sound",
+            SaucerKind::Small => "saucer-small-sound",
+        }
+    }
+
+    // Small saucers become more common as the score climbs
+    pub fn choose(level: i32, score: i32) -> SaucerKind {
+        let small_chance = (0.2 + 0.05 * (level - 1) as f64 + score as f64 / 40000.0).min(0.9);
+        if rng::random() < small_chance {
+            SaucerKind::Small
+        } else {
+            SaucerKind::Large
+        }
+    }
+}
+
+pub struct Saucer {
+    pub kind: SaucerKind,
+    pub position: Vector,
+    velocity: Vector,
+    zig_timer: i32,
+    fire_timer: i32,
+    sound_timer: i32,
+}
+
+impl Saucer {
+    // Enters from the left or right edge at a random height
+    pub fn new(kind: SaucerKind) -> Self {
+        let from_left = rng::random() < 0.5;
+        let radius = kind.radius();
+        Saucer {
+            kind,
+            position: Vector {
+                x: if from_left { -radius } else { WORLD_WIDTH + radius },
+                y: WORLD_HEIGHT * (0.15 + rng::random() * 0.7),
+            },
+            velocity: Vector { x: if from_left { kind.speed() } else { -kind.speed() }, y: 0.0 },
+            zig_timer: 60,
+            fire_timer: kind.fire_interval(),
+            sound_timer: 0,
+        }
+    }
+
+    // Returns false once the saucer has crossed to the far edge. `time_scale`
+    // slows its flight while time is dilated.
+    pub fn update(&mut self, time_scale: f64) -> bool {
+        // Zig-zag: every second or two, veer up, down or level out
+        self.zig_timer -= 1;
+        if self.zig_timer <= 0 {
+            let heading = (rng::random() * 3.0).floor() - 1.0;
+            self.velocity.y = heading * self.kind.speed() * 0.6;
+            self.zig_timer = 60 + (rng::random() * 60.0) as i32;
+        }
+
+        self.position.x += self.velocity.x * time_scale;
+        self.position.y = (self.position.y + self.velocity.y * time_scale).rem_euclid(WORLD_HEIGHT);
+
+        let radius = self.kind.radius();
+        self.position.x >= -radius && self.position.x <= WORLD_WIDTH + radius
+    }
+
+    pub fn ready_to_fire(&mut self) -> bool {
+        self.fire_timer -= 1;
+        if self.fire_timer > 0 {
+            return false;
+        }
+        self.fire_timer = self.kind.fire_interval();
+        true
+    }
+
+    pub fn ready_to_hum(&mut self) -> bool {
+        self.sound_timer -= 1;
+        if self.sound_timer > 0 {
+            return false;
+        }
+        self.sound_timer = 15;
+        true
+    }
+
+    // Large saucers spray at random; small ones aim at the nearest image of
+    // the ship, with an error that shrinks as level and score increase
+    pub fn firing_angle(&self, target: &Vector, level: i32, score: i32) -> f64 {
+        match self.kind {
+            SaucerKind::Large => rng::random() * 2.0 * PI,
+            SaucerKind::Small => {
+                let delta = wrap_delta(&self.position, target);
+                let max_error = (0.6 - 0.05 * (level - 1) as f64 - score as f64 / 100000.0).max(0.05);
+                delta.x.atan2(-delta.y) + (rng::random() * 2.0 - 1.0) * max_error
+            }
+        }
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+        let r = self.kind.radius();
+        ctx.save();
+        ctx.translate(self.position.x, self.position.y).unwrap();
+        ctx.set_stroke_style_str("white");
+        ctx.set_line_width(theme.line_scale);
+
+        // Hull
+        ctx.begin_path();
+        ctx.move_to(-r, 0.0);
+        ctx.line_to(-0.4 * r, -0.35 * r);
+        ctx.line_to(0.4 * r, -0.35 * r);
+        ctx.line_to(r, 0.0);
+        ctx.line_to(0.4 * r, 0.35 * r);
+        ctx.line_to(-0.4 * r, 0.35 * r);
+        ctx.close_path();
+        ctx.move_to(-r, 0.0);
+        ctx.line_to(r, 0.0);
+
+        // Dome
+        ctx.move_to(-0.4 * r, -0.35 * r);
+        ctx.line_to(-0.25 * r, -0.7 * r);
+        ctx.line_to(0.25 * r, -0.7 * r);
+        ctx.line_to(0.4 * r, -0.35 * r);
+        ctx.stroke();
+
+        ctx.restore();
+    }
+}
@@ -0,0 +1,187 @@
+use web_sys::CanvasRenderingContext2d;
+
+use crate::{Vector, WORLD_WIDTH};
+
+// Hits within this many frames of each other keep a combo going
+const COMBO_WINDOW: i32 = 120;
+// Every few hits in a row add one to the combo multiplier
+const HITS_PER_STEP: i32 = 3;
+const MAX_COMBO_MULTIPLIER: i32 = 8;
+
+// Rocks that pass this close to the ship's hull without touching it earn a
+// near-miss bonus
+pub const NEAR_MISS_MARGIN: f64 = 15.0;
+pub const NEAR_MISS_BONUS: i32 = 50;
+
+const POPUP_FRAMES: i32 = 45;
+
+pub struct Combo {
+    pub hits: i32,
+    timer: i32,
+}
+
+impl Combo {
+    pub fn new() -> Self {
+        Combo { hits: 0, timer: 0 }
+    }
+
+    pub fn multiplier(&self) -> i32 {
+        (1 + self.hits / HITS_PER_STEP).min(MAX_COMBO_MULTIPLIER)
+    }
+
+    pub fn fraction_left(&self) -> f64 {
+        self.timer as f64 / COMBO_WINDOW as f64
+    }
+
+    // Returns true if the hit raised the multiplier
+    pub fn hit(&mut self) -> bool {
+        let before = self.multiplier();
+        self.hits += 1;
+        self.timer = COMBO_WINDOW;
+        self.multiplier() > before
+    }
+
+    // Returns true if the combo had a multiplier to lose
+    pub fn reset(&mut self) -> bool {
+        let had_multiplier = self.multiplier() > 1;
+        self.hits = 0;
+        self.timer = 0;
+        had_multiplier
+    }
+
+    // Returns true if the combo just timed out with a multiplier to lose
+    pub fn tick(&mut self) -> bool {
+        if self.hits == 0 {
+            return false;
+        }
+        self.timer -= 1;
+        self.timer <= 0 && self.reset()
+    }
+}
+
+// Tracks which rocks are grazing the ship, so each pass is only counted once
+pub struct NearMisses {
+    grazing: Vec<u32>,
+}
+
+impl NearMisses {
+    pub fn new() -> Self {
+        NearMisses { grazing: Vec::new() }
+    }
+
+    pub fn clear(&mut self) {
+        self.grazing.clear();
+    }
+
+    // Takes the rocks grazing the ship this frame and returns those that
+    // were grazing it last frame but have now slipped away
+    pub fn update(&mut self, grazing: Vec<u32>) -> Vec<u32> {
+        let passed = self.grazing.iter().filter(|id| !grazing.contains(id)).copied().collect();
+        self.grazing = grazing;
+        passed
+    }
+}
+
+// Points floating up from where they were scored
+pub struct ScorePopup {
+    position: Vector,
+    text: String,
+    timer: i32,
+}
+
+impl ScorePopup {
+    pub fn new(position: &Vector, text: String) -> Self {
+        ScorePopup { position: position.clone(), text, timer: POPUP_FRAMES }
+    }
+
+    pub fn update(&mut self) -> bool {
+        self.position.y -= 0.7;
+        self.timer -= 1;
+        self.timer > 0
+    }
+
+    // Drawn over the playfield, so text stays readable in a mirror world;
+    // `mirrored` moves it to match the flipped rocks
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, mirrored: bool, color: &str) {
+        let x = if mirrored { WORLD_WIDTH - self.position.x } else { self.position.x };
+        ctx.save();
+        ctx.set_global_alpha(self.timer as f64 / POPUP_FRAMES as f64);
+        ctx.set_fill_style_str(color);
+        ctx.set_text_align("center");
+        ctx.fill_text(&self.text, x, self.position.y).unwrap();
+        ctx.restore();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiplier_steps_up_every_few_hits_and_caps() {
+        let mut combo = Combo::new();
+        assert_eq!(combo.multiplier(), 1);
+        let raised: Vec<bool> = (0..HITS_PER_STEP).map(|_| combo.hit()).collect();
+        assert_eq!(raised, [false, false, true]);
+        assert_eq!(combo.multiplier(), 2);
+
+        for _ in 0..100 {
+            combo.hit();
+        }
+        assert_eq!(combo.multiplier(), MAX_COMBO_MULTIPLIER);
+        assert!(!combo.hit());
+    }
+
+    #[test]
+    fn combo_times_out_after_the_window() {
+        let mut combo = Combo::new();
+        for _ in 0..HITS_PER_STEP {
+            combo.hit();
+        }
+        assert_eq!(combo.fraction_left(), 1.0);
+        for _ in 1..COMBO_WINDOW {
+            assert!(!combo.tick());
+        }
+        assert!(combo.tick());
+        assert_eq!((combo.hits, combo.multiplier()), (0, 1));
+        assert!(!combo.tick());
+    }
+
+    #[test]
+    fn each_hit_restarts_the_window() {
+        let mut combo = Combo::new();
+        combo.hit();
+        for _ in 0..COMBO_WINDOW - 1 {
+            combo.tick();
+        }
+        combo.hit();
+        for _ in 0..COMBO_WINDOW - 1 {
+            combo.tick();
+        }
+        assert_eq!(combo.hits, 2);
+
+        // Losing a combo with no multiplier yet isn't worth announcing
+        assert!(!combo.tick());
+        assert_eq!(combo.hits, 0);
+        for _ in 0..HITS_PER_STEP {
+            combo.hit();
+        }
+        assert!(combo.reset());
+        assert!(!combo.reset());
+    }
+
+    #[test]
+    fn near_miss_counts_once_when_the_rock_slips_away() {
+        let mut near_misses = NearMisses::new();
+        assert!(near_misses.update(vec![1, 2]).is_empty());
+        assert!(near_misses.update(vec![1, 2]).is_empty());
+        assert_eq!(near_misses.update(vec![2, 3]), [1]);
+        assert_eq!(near_misses.update(vec![]), [2, 3]);
+        assert!(near_misses.update(vec![]).is_empty());
+
+        // Nothing counts while the ship is shielded or out of play
+        near_misses.update(vec![4]);
+        near_misses.clear();
+        assert!(near_misses.update(vec![]).is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::accessibility::AccessibilitySettings;
+use crate::collision::CollisionSettings;
+use crate::disruption::DisruptionSettings;
+use crate::hyperspace::HyperspaceSettings;
+use crate::powerup::PowerUpSettings;
+use crate::shockwave::ShockWaveSettings;
+use crate::weapon::{BulletSettings, WeaponSettings};
+use crate::input::ControlScheme;
+use crate::particles::ParticleQuality;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "difficulty-easy",
+            Difficulty::Normal => "difficulty-normal",
+            Difficulty::Hard => "difficulty-hard",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    // Scales space rock speed on top of the level progression
+    pub fn speed_factor(&self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.3,
+        }
+    }
+
+    pub fn starting_lives(&self) -> i32 {
+        match self {
+            Difficulty::Easy => 5,
+            Difficulty::Normal => 3,
+            Difficulty::Hard => 2,
+        }
+    }
+
+    pub fn cycled(&self, step: i32) -> Difficulty {
+        const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+        cycle(&ALL, *self, step)
+    }
+}
+
+pub struct ExtraLifeSettings {
+    // Points between extra lives; 0 turns them off
+    pub interval: i32,
+    // Extra lives stop once the player has this many; 0 for no cap
+    pub max_lives: i32,
+}
+
+impl ExtraLifeSettings {
+    pub fn new() -> Self {
+        ExtraLifeSettings { interval: 10000, max_lives: 0 }
+    }
+}
+
+pub struct Settings {
+    pub volume: f64,
+    pub difficulty: Difficulty,
+    pub control_scheme: ControlScheme,
+    pub particles: ParticleQuality,
+    pub accessibility: AccessibilitySettings,
+    pub hyperspace: HyperspaceSettings,
+    pub power_ups: PowerUpSettings,
+    pub weapon: WeaponSettings,
+    pub bullets: BulletSettings,
+    pub disruptions: DisruptionSettings,
+    pub shock_waves: ShockWaveSettings,
+    pub collisions: CollisionSettings,
+    pub three_way_split: bool,
+    pub extra_lives: ExtraLifeSettings,
+    // Planets and black holes placed by the level definitions
+    pub gravity_wells: bool,
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Settings {
+            volume: 0.8,
+            difficulty: Difficulty::Normal,
+            control_scheme: ControlScheme::Keyboard,
+            particles: ParticleQuality::High,
+            accessibility: AccessibilitySettings::new(),
+            hyperspace: HyperspaceSettings::new(),
+            power_ups: PowerUpSettings::new(),
+            weapon: WeaponSettings::new(),
+            bullets: BulletSettings::new(),
+            disruptions: DisruptionSettings::new(),
+            shock_waves: ShockWaveSettings::new(),
+            collisions: CollisionSettings::new(),
+            three_way_split: false,
+            extra_lives: ExtraLifeSettings::new(),
+            gravity_wells: true,
+        }
+    }
+}
+
+// Step through a fixed list of options, wrapping at either end
+pub fn cycle<T: Copy + PartialEq>(options: &[T], current: T, step: i32) -> T {
+    let index = options.iter().position(|option| *option == current).unwrap_or(0) as i32;
+    let next = (index + step).rem_euclid(options.len() as i32);
+    options[next as usize]
+}
@@ -0,0 +1,193 @@
+use std::f64::consts::PI;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::accessibility::Theme;
+use crate::Vector;
+
+pub const SHIELD_RADIUS: f64 = 28.0;
+pub const SHIELD_RESTITUTION: f64 = 0.8;
+pub const MAX_ENERGY: f64 = 100.0;
+const DRAIN_PER_FRAME: f64 = 0.4; // About 4 seconds of continuous use
+const IMPACT_COST: f64 = 20.0;
+const RECHARGE_PER_FRAME: f64 = 0.15;
+const RECHARGE_DELAY: i32 = 60; // 1 second at 60 FPS after the shield drops
+const MIN_RAISE_ENERGY: f64 = 20.0;
+
+pub enum ShieldChange {
+    Raised,
+    Depleted,
+}
+
+pub struct Shield {
+    pub energy: f64,
+    pub active: bool,
+    // Set each frame by whichever input is holding the shield up
+    pub requested: bool,
+    // Held up for free by the shield power-up, also set each frame
+    pub sustained: bool,
+    recharge_delay: i32,
+}
+
+impl Shield {
+    pub fn new() -> Self {
+        Shield { energy: MAX_ENERGY, active: false, requested: false, sustained: false, recharge_delay: 0 }
+    }
+
+    pub fn update(&mut self, blocked: bool) -> Option<ShieldChange> {
+        let wanted = (self.requested || self.sustained) && !blocked;
+        let mut change = None;
+
+        if self.active && !wanted {
+            self.lower();
+        } else if !self.active && wanted && (self.sustained || self.energy >= MIN_RAISE_ENERGY) {
+            self.active = true;
+            change = Some(ShieldChange::Raised);
+        }
+
+        if self.active && self.sustained {
+            // Free while the power-up lasts
+        } else if self.active {
+            self.energy -= DRAIN_PER_FRAME;
+            if self.energy <= 0.0 {
+                self.energy = 0.0;
+                self.lower();
+                change = Some(ShieldChange::Depleted);
+            }
+        } else if self.recharge_delay > 0 {
+            self.recharge_delay -= 1;
+        } else {
+            self.energy = (self.energy + RECHARGE_PER_FRAME).min(MAX_ENERGY);
+        }
+        change
+    }
+
+    // Returns true if the hit used up the last of the energy
+    pub fn absorb_impact(&mut self) -> bool {
+        if self.sustained {
+            return false;
+        }
+        self.energy -= IMPACT_COST;
+        if self.energy <= 0.0 {
+            self.energy = 0.0;
+            self.lower();
+            return true;
+        }
+        false
+    }
+
+    fn lower(&mut self) {
+        self.active = false;
+        self.recharge_delay = RECHARGE_DELAY;
+    }
+
+    // The bubble fades as energy runs down and flickers when nearly empty
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, position: &Vector, theme: &Theme) {
+        if !self.active {
+            return;
+        }
+
+        let charge = self.energy / MAX_ENERGY;
+        let mut alpha = 0.35 + 0.65 * charge;
+        // Energy drains steadily while raised, so it doubles as a flicker clock
+        if charge < 0.2 && !theme.reduced_flash && (self.energy / 1.6) as i32 % 2 == 0 {
+            alpha *= 0.4;
+        }
+
+        ctx.save();
+        ctx.set_global_alpha(alpha);
+        ctx.set_stroke_style_str(theme.highlight);
+        ctx.set_line_width(2.0 * theme.line_scale);
+        ctx.begin_path();
+        ctx.arc(position.x, position.y, SHIELD_RADIUS, 0.0, 2.0 * PI).unwrap();
+        ctx.stroke();
+        ctx.restore();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn held_shield_drains_then_drops() {
+        let mut shield = Shield::new();
+        shield.requested = true;
+        assert!(matches!(shield.update(false), Some(ShieldChange::Raised)));
+
+        let mut frames = 1;
+        loop {
+            frames += 1;
+            if matches!(shield.update(false), Some(ShieldChange::Depleted)) {
+                break;
+            }
+            assert!(shield.active);
+        }
+        assert_eq!(frames, (MAX_ENERGY / DRAIN_PER_FRAME).round() as i32);
+        assert!(!shield.active);
+        assert_eq!(shield.energy, 0.0);
+    }
+
+    #[test]
+    fn recharge_starts_after_a_delay() {
+        let mut shield = Shield::new();
+        shield.requested = true;
+        for _ in 0..100 {
+            shield.update(false);
+        }
+        // The delay counts down from the frame the shield drops
+        shield.requested = false;
+        shield.update(false);
+        let lowered_at = shield.energy;
+        for _ in 1..RECHARGE_DELAY {
+            shield.update(false);
+        }
+        assert_eq!(shield.energy, lowered_at);
+        shield.update(false);
+        assert_eq!(shield.energy, lowered_at + RECHARGE_PER_FRAME);
+
+        for _ in 0..10_000 {
+            shield.update(false);
+        }
+        assert_eq!(shield.energy, MAX_ENERGY);
+    }
+
+    #[test]
+    fn a_nearly_empty_shield_cannot_be_raised() {
+        let mut shield = Shield::new();
+        shield.energy = MIN_RAISE_ENERGY - 1.0;
+        shield.requested = true;
+        assert!(shield.update(false).is_none());
+        assert!(!shield.active);
+
+        // The power-up holds it up regardless, for free
+        shield.sustained = true;
+        assert!(matches!(shield.update(false), Some(ShieldChange::Raised)));
+        let energy = shield.energy;
+        shield.update(false);
+        assert!(!shield.absorb_impact());
+        assert_eq!(shield.energy, energy);
+    }
+
+    #[test]
+    fn impacts_cost_energy_and_can_break_the_shield() {
+        let mut shield = Shield::new();
+        shield.requested = true;
+        shield.update(false);
+        let before = shield.energy;
+        assert!(!shield.absorb_impact());
+        assert_eq!(shield.energy, before - IMPACT_COST);
+
+        shield.energy = IMPACT_COST / 2.0;
+        assert!(shield.absorb_impact());
+        assert!(!shield.active);
+    }
+
+    #[test]
+    fn blocked_shield_drops_while_held() {
+        let mut shield = Shield::new();
+        shield.requested = true;
+        shield.update(false);
+        assert!(shield.update(true).is_none());
+        assert!(!shield.active);
+    }
+}
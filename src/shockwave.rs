@@ -0,0 +1,136 @@
+use std::f64::consts::PI;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::accessibility::Theme;
+use crate::{wrap_delta, Vector};
+
+// Extra points per rock a wave destroys, multiplied by the chain depth
+pub const CHAIN_BONUS: i32 = 25;
+
+pub struct ShockWaveSettings {
+    // Outward speed given to a ship-sized body right at the centre; it fades
+    // to nothing as the wave reaches its full size
+    pub push: f64,
+    // Rocks smaller than this are destroyed when the wave overtakes them
+    pub destroy_below: f64,
+    // Larger rocks are split by the wave instead of just being pushed
+    pub split_large: bool,
+}
+
+impl ShockWaveSettings {
+    pub fn new() -> Self {
+        ShockWaveSettings { push: 2.0, destroy_below: 20.0, split_large: false }
+    }
+}
+
+pub struct ShockWave {
+    position: Vector,
+    radius: f64,
+    // The wavefront covers the ring between the last two radii
+    previous_radius: f64,
+    max_radius: f64,
+    alpha: f64,
+    lifetime: i32,
+    pub by_player: bool,
+    // 1 for a wave from a rock the player shot, 2 for a wave set off by
+    // that wave, and so on
+    pub chain: i32,
+    // Rocks this wave has already reached, so each is affected only once
+    struck: Vec<u32>,
+    struck_ship: bool,
+}
+
+impl ShockWave {
+    pub fn new(x: f64, y: f64, by_player: bool, chain: i32) -> Self {
+        ShockWave {
+            position: Vector { x, y },
+            radius: 0.0,
+            previous_radius: 0.0,
+            max_radius: 150.0,
+            alpha: 1.0,
+            lifetime: 60,
+            by_player,
+            chain,
+            struck: Vec::new(),
+            struck_ship: false,
+        }
+    }
+
+    pub fn update(&mut self) -> bool {
+        self.previous_radius = self.radius;
+        self.radius += 3.0;
+        self.alpha = (self.lifetime as f64) / 60.0;
+        self.lifetime -= 1;
+        self.lifetime > 0 && self.radius < self.max_radius
+    }
+
+    pub fn strength(&self) -> f64 {
+        (1.0 - self.radius / self.max_radius).max(0.0)
+    }
+
+    // Unit vector pointing away from the centre if the wavefront overlaps a
+    // body of radius `size` at `position`
+    fn front_direction(&self, position: &Vector, size: f64) -> Option<Vector> {
+        let delta = wrap_delta(&self.position, position);
+        let distance = (delta.x * delta.x + delta.y * delta.y).sqrt();
+        if distance - size > self.radius || distance + size < self.previous_radius {
+            return None;
+        }
+        if distance == 0.0 {
+            return Some(Vector { x: 0.0, y: -1.0 });
+        }
+        Some(Vector { x: delta.x / distance, y: delta.y / distance })
+    }
+
+    // Outward direction the first time the wavefront reaches rock `id`
+    pub fn strike(&mut self, id: u32, position: &Vector, size: f64) -> Option<Vector> {
+        if self.struck.contains(&id) {
+            return None;
+        }
+        let direction = self.front_direction(position, size)?;
+        self.struck.push(id);
+        Some(direction)
+    }
+
+    pub fn strike_ship(&mut self, position: &Vector, size: f64) -> Option<Vector> {
+        if self.struck_ship {
+            return None;
+        }
+        let direction = self.front_direction(position, size)?;
+        self.struck_ship = true;
+        Some(direction)
+    }
+
+    // Keeps rocks that appear inside the wave (such as fragments) out of it
+    pub fn exclude(&mut self, id: u32) {
+        self.struck.push(id);
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+        ctx.save();
+        ctx.set_stroke_style_str(theme.highlight);
+
+        // Reduced flash: one soft ring that fades gently instead of a bright triple flash
+        let rings = if theme.reduced_flash {
+            ctx.set_global_alpha(self.alpha * 0.4);
+            ctx.set_line_width(2.0 * theme.line_scale);
+            1
+        } else {
+            ctx.set_global_alpha(self.alpha);
+            ctx.set_line_width(3.0 * theme.line_scale);
+            3
+        };
+
+        // Draw multiple expanding rings
+        for i in 0..rings {
+            let ring_radius = self.radius - (i as f64 * 15.0);
+            if ring_radius > 0.0 {
+                ctx.begin_path();
+                ctx.arc(self.position.x, self.position.y, ring_radius, 0.0, 2.0 * PI).unwrap();
+                ctx.stroke();
+            }
+        }
+
+        ctx.restore();
+    }
+}
@@ -0,0 +1,119 @@
+// Top-level phases of a game session. Menus hang off Title and Paused.
+#[derive(Clone, Copy, PartialEq)]
+pub enum GameState {
+    Title,
+    Playing,
+    Paused,
+    Respawning,
+    LevelTransition,
+    GameOver,
+}
+
+impl GameState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameState::Title => "title",
+            GameState::Playing => "playing",
+            GameState::Paused => "paused",
+            GameState::Respawning => "respawning",
+            GameState::LevelTransition => "level_transition",
+            GameState::GameOver => "game_over",
+        }
+    }
+
+    // States in which the ship flies and rocks move
+    pub fn is_active_play(&self) -> bool {
+        matches!(self, GameState::Playing | GameState::Respawning)
+    }
+
+    // Only here can the ship be destroyed: losing it moves on to Respawning
+    // or GameOver, which no other state can reach
+    pub fn can_lose_ship(&self) -> bool {
+        matches!(self, GameState::Playing)
+    }
+
+    pub fn can_pause(&self) -> bool {
+        matches!(self, GameState::Playing | GameState::Respawning | GameState::LevelTransition)
+    }
+
+    pub fn can_transition_to(&self, next: GameState) -> bool {
+        use GameState::*;
+
+        match (self, next) {
+            // A new game can be started from anywhere
+            (_, Playing) => true,
+            (Title, _) => false,
+            (Playing, Paused | Respawning | LevelTransition | GameOver) => true,
+            (Respawning, Paused | LevelTransition) => true,
+            (LevelTransition, Paused) => true,
+            // Resuming returns to whichever state was paused
+            (Paused, Respawning | LevelTransition | Title) => true,
+            (GameOver, Title) => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [GameState; 6] = [
+        GameState::Title,
+        GameState::Playing,
+        GameState::Paused,
+        GameState::Respawning,
+        GameState::LevelTransition,
+        GameState::GameOver,
+    ];
+
+    // Losing the ship must always be able to move on, or lives drain away
+    // every frame without ever reaching game over
+    #[test]
+    fn ship_is_only_lost_where_respawn_and_game_over_are_reachable() {
+        for state in ALL {
+            if state.can_lose_ship() {
+                assert!(state.can_transition_to(GameState::Respawning), "{} can't respawn", state.name());
+                assert!(state.can_transition_to(GameState::GameOver), "{} can't end the game", state.name());
+            }
+        }
+        assert!(!GameState::Respawning.can_lose_ship());
+    }
+
+    #[test]
+    fn transition_table() {
+        use GameState::*;
+        let allowed = [
+            (Playing, Paused),
+            (Playing, Respawning),
+            (Playing, LevelTransition),
+            (Playing, GameOver),
+            (Respawning, Paused),
+            (Respawning, LevelTransition),
+            (LevelTransition, Paused),
+            (Paused, Respawning),
+            (Paused, LevelTransition),
+            (Paused, Title),
+            (GameOver, Title),
+        ];
+        for from in ALL {
+            for to in ALL {
+                // A new game can start from anywhere
+                let expected = to == Playing || allowed.contains(&(from, to));
+                assert_eq!(from.can_transition_to(to), expected, "{} -> {}", from.name(), to.name());
+            }
+        }
+    }
+
+    #[test]
+    fn pausable_states_can_pause_and_resume() {
+        for state in ALL {
+            if state.can_pause() {
+                assert!(state.can_transition_to(GameState::Paused), "{} can't pause", state.name());
+                assert!(GameState::Paused.can_transition_to(state), "{} can't resume", state.name());
+            }
+        }
+        assert!(!GameState::Title.can_pause());
+        assert!(!GameState::GameOver.can_pause());
+    }
+}
@@ -0,0 +1,603 @@
+use std::f64::consts::PI;
+use web_sys::CanvasRenderingContext2d;
+
+use crate::accessibility::Theme;
+use crate::{wrap_delta, Asteroid, Vector, WORLD_HEIGHT, WORLD_WIDTH};
+
+pub const MAX_HEAT: f64 = 100.0;
+pub const LASER_RANGE: f64 = 300.0;
+pub const LASER_HIT_INTERVAL: i32 = 6;
+// Long enough to bridge the gap between repeated fire inputs
+const LASER_BEAM_FRAMES: i32 = 14;
+pub const MAX_MINES: usize = 4;
+pub const MINE_BLAST_RADIUS: f64 = 70.0;
+const MINE_TRIGGER_DISTANCE: f64 = 25.0;
+const MINE_ARM_FRAMES: i32 = 30;
+const MINE_LIFETIME: i32 = 1200;
+const MISSILE_SPEED: f64 = 5.0;
+const MISSILE_TURN_RATE: f64 = 0.08;
+pub const SHOTGUN_PELLETS: usize = 6;
+pub const SHOTGUN_SPREAD: f64 = 0.35;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WeaponKind {
+    Blaster,
+    Laser,
+    Missiles,
+    Mines,
+    Shotgun,
+}
+
+const ALL_WEAPONS: [WeaponKind; 5] =
+    [WeaponKind::Blaster, WeaponKind::Laser, WeaponKind::Missiles, WeaponKind::Mines, WeaponKind::Shotgun];
+
+impl WeaponKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeaponKind::Blaster => "blaster",
+            WeaponKind::Laser => "laser",
+            WeaponKind::Missiles => "missiles",
+            WeaponKind::Mines => "mines",
+            WeaponKind::Shotgun => "shotgun",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<WeaponKind> {
+        ALL_WEAPONS.iter().copied().find(|kind| kind.name() == name)
+    }
+
+    // 1-based slot for the number keys
+    pub fn from_slot(slot: usize) -> Option<WeaponKind> {
+        slot.checked_sub(1).and_then(|index| ALL_WEAPONS.get(index).copied())
+    }
+
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            WeaponKind::Blaster => "weapon-blaster",
+            WeaponKind::Laser => "weapon-laser",
+            WeaponKind::Missiles => "weapon-missiles",
+            WeaponKind::Mines => "weapon-mines",
+            WeaponKind::Shotgun => "weapon-shotgun",
+        }
+    }
+
+    pub fn sound_id(&self) -> &'static str {
+        match self {
+            WeaponKind::Blaster => "shoot-sound",
+            WeaponKind::Laser => "laser-sound",
+            WeaponKind::Missiles => "missile-sound",
+            WeaponKind::Mines => "mine-drop-sound",
+            WeaponKind::Shotgun => "shotgun-sound",
+        }
+    }
+
+    // Blaster ammo is unlimited; laser ammo is frames of beam
+    pub fn max_ammo(&self) -> Option<i32> {
+        match self {
+            WeaponKind::Blaster => None,
+            WeaponKind::Laser => Some(300),
+            WeaponKind::Missiles => Some(10),
+            WeaponKind::Mines => Some(6),
+            WeaponKind::Shotgun => Some(20),
+        }
+    }
+
+    fn cooldown_frames(&self, settings: &WeaponSettings) -> i32 {
+        match self {
+            WeaponKind::Blaster => settings.cooldown_frames,
+            WeaponKind::Laser => 0,
+            WeaponKind::Missiles => 30,
+            WeaponKind::Mines => 40,
+            WeaponKind::Shotgun => 35,
+        }
+    }
+
+    fn heat(&self, settings: &WeaponSettings) -> f64 {
+        match self {
+            WeaponKind::Shotgun => settings.heat_per_shot * 2.0,
+            _ => settings.heat_per_shot,
+        }
+    }
+
+    fn index(&self) -> usize {
+        ALL_WEAPONS.iter().position(|kind| kind == self).unwrap_or(0)
+    }
+}
+
+pub struct WeaponSettings {
+    // Frames between blaster shots
+    pub cooldown_frames: i32,
+    // Live blaster bullets and shotgun pellets allowed at once; 0 means no limit
+    pub max_bullets: usize,
+    pub overheat: bool,
+    pub heat_per_shot: f64,
+    pub cooling_per_frame: f64,
+    // Once overheated, firing resumes when heat falls to this level
+    pub recover_heat: f64,
+}
+
+impl WeaponSettings {
+    pub fn new() -> Self {
+        WeaponSettings {
+            cooldown_frames: 8,
+            max_bullets: 8,
+            overheat: false,
+            heat_per_shot: 9.0,
+            cooling_per_frame: 0.6,
+            recover_heat: 40.0,
+        }
+    }
+
+    // Bullet slots still free with `live` bullets in flight
+    pub fn free_slots(&self, live: usize) -> usize {
+        if self.max_bullets == 0 {
+            usize::MAX
+        } else {
+            self.max_bullets.saturating_sub(live)
+        }
+    }
+}
+
+pub struct BulletSettings {
+    // Pixels per frame, before the ship's own velocity is added
+    pub muzzle_speed: f64,
+    pub inherit_velocity: bool,
+    // A bullet expires after this many frames or once it has travelled
+    // `range` pixels, whichever comes first
+    pub lifetime: i32,
+    pub range: f64,
+    pub wrap: bool,
+    // Lets the player's own bullets destroy the ship
+    pub self_hit: bool,
+}
+
+impl BulletSettings {
+    pub fn new() -> Self {
+        BulletSettings {
+            muzzle_speed: 10.0,
+            inherit_velocity: true,
+            lifetime: 60,
+            range: 600.0,
+            wrap: true,
+            self_hit: false,
+        }
+    }
+}
+
+pub struct WeaponState {
+    pub current: WeaponKind,
+    ammo: [i32; 5],
+    cooldowns: [i32; 5],
+    pub heat: f64,
+    pub overheated: bool,
+    // Frames the laser keeps firing, and until its next hit lands
+    pub beam_frames: i32,
+    pub laser_hit_timer: i32,
+}
+
+impl WeaponState {
+    pub fn new() -> Self {
+        let mut state = WeaponState {
+            current: WeaponKind::Blaster,
+            ammo: [0; 5],
+            cooldowns: [0; 5],
+            heat: 0.0,
+            overheated: false,
+            beam_frames: 0,
+            laser_hit_timer: 0,
+        };
+        state.restock();
+        state
+    }
+
+    pub fn restock(&mut self) {
+        for kind in ALL_WEAPONS {
+            self.ammo[kind.index()] = kind.max_ammo().unwrap_or(0);
+        }
+    }
+
+    pub fn ammo(&self, kind: WeaponKind) -> Option<i32> {
+        kind.max_ammo().map(|_| self.ammo[kind.index()])
+    }
+
+    pub fn has_ammo(&self, kind: WeaponKind) -> bool {
+        self.ammo(kind).is_none_or(|ammo| ammo > 0)
+    }
+
+    pub fn beam_active(&self) -> bool {
+        self.beam_frames > 0
+    }
+
+    pub fn tick(&mut self, settings: &WeaponSettings) {
+        for cooldown in &mut self.cooldowns {
+            *cooldown = (*cooldown - 1).max(0);
+        }
+
+        // The beam burns ammo and heat for every frame it is on
+        if self.beam_frames > 0 {
+            self.beam_frames -= 1;
+            self.laser_hit_timer = (self.laser_hit_timer - 1).max(0);
+            let laser = WeaponKind::Laser.index();
+            self.ammo[laser] = (self.ammo[laser] - 1).max(0);
+            if settings.overheat {
+                self.heat += settings.heat_per_shot * 0.1 + settings.cooling_per_frame;
+            }
+            if self.ammo[laser] == 0 || self.current != WeaponKind::Laser {
+                self.beam_frames = 0;
+            }
+        }
+
+        self.heat = (self.heat - settings.cooling_per_frame).clamp(0.0, MAX_HEAT);
+        if settings.overheat && self.heat >= MAX_HEAT {
+            self.overheated = true;
+            self.beam_frames = 0;
+        }
+        if self.overheated && self.heat <= settings.recover_heat {
+            self.overheated = false;
+        }
+    }
+
+    pub fn ready(&self, settings: &WeaponSettings, live_bullets: usize) -> bool {
+        let kind = self.current;
+        let uses_slots = matches!(kind, WeaponKind::Blaster | WeaponKind::Shotgun);
+        let slots_free = !uses_slots || settings.free_slots(live_bullets) > 0;
+        self.cooldowns[kind.index()] == 0 && !self.overheated && self.has_ammo(kind) && slots_free
+    }
+
+    // Spends ammo, starts the cooldown and adds heat. Returns true if this
+    // shot overheated the weapon.
+    pub fn fired(&mut self, settings: &WeaponSettings, rapid_fire: bool) -> bool {
+        let kind = self.current;
+        let cooldown = kind.cooldown_frames(settings);
+        self.cooldowns[kind.index()] = if rapid_fire { cooldown / 2 } else { cooldown };
+
+        if kind == WeaponKind::Laser {
+            self.beam_frames = LASER_BEAM_FRAMES;
+            return false; // Ammo and heat are spent per frame of beam
+        }
+        if kind.max_ammo().is_some() {
+            self.ammo[kind.index()] -= 1;
+        }
+
+        if !settings.overheat {
+            return false;
+        }
+        self.heat = (self.heat + kind.heat(settings)).min(MAX_HEAT);
+        if self.heat >= MAX_HEAT {
+            self.overheated = true;
+            return true;
+        }
+        false
+    }
+
+    pub fn select(&mut self, kind: WeaponKind) {
+        self.current = kind;
+    }
+
+    // Step to the next weapon that still has ammo
+    pub fn cycle(&mut self, step: i32) {
+        let count = ALL_WEAPONS.len() as i32;
+        let start = self.current.index() as i32;
+        for offset in 1..=count {
+            let kind = ALL_WEAPONS[(start + step * offset).rem_euclid(count) as usize];
+            if self.has_ammo(kind) {
+                self.current = kind;
+                return;
+            }
+        }
+    }
+}
+
+// Distance along a ray from `origin` in `direction` (a unit vector) to the
+// surface of a circle, if it lies within `range`. A ray starting inside the
+// circle hits it straight away.
+pub fn ray_cast_circle(origin: &Vector, direction: &Vector, range: f64, center: &Vector, radius: f64) -> Option<f64> {
+    let offset = wrap_delta(origin, center);
+    let along = offset.x * direction.x + offset.y * direction.y;
+    let across_x = offset.x - along * direction.x;
+    let across_y = offset.y - along * direction.y;
+    let across_squared = across_x * across_x + across_y * across_y;
+    let radius_squared = radius * radius;
+    if across_squared > radius_squared {
+        return None;
+    }
+    let entry = along - (radius_squared - across_squared).sqrt();
+    if (0.0..=range).contains(&entry) || (entry < 0.0 && along >= 0.0) {
+        Some(entry.max(0.0))
+    } else {
+        None
+    }
+}
+
+// The first rock a ray meets within `range`, with the distance to its surface
+pub fn ray_cast(origin: &Vector, direction: &Vector, range: f64, asteroids: &[Asteroid]) -> Option<(usize, f64)> {
+    asteroids
+        .iter()
+        .enumerate()
+        .filter_map(|(index, asteroid)| {
+            ray_cast_circle(origin, direction, range, &asteroid.position, asteroid.size).map(|distance| (index, distance))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+// Draws a beam, repeated across the wrapped edges so it can leave one side
+// of the screen and carry on from the other
+pub fn draw_beam(ctx: &CanvasRenderingContext2d, origin: &Vector, direction: &Vector, length: f64, theme: &Theme) {
+    ctx.save();
+    ctx.set_stroke_style_str(theme.highlight);
+    ctx.set_line_width(3.0 * theme.line_scale);
+    for shift_x in [-WORLD_WIDTH, 0.0, WORLD_WIDTH] {
+        for shift_y in [-WORLD_HEIGHT, 0.0, WORLD_HEIGHT] {
+            ctx.begin_path();
+            ctx.move_to(origin.x + shift_x, origin.y + shift_y);
+            ctx.line_to(origin.x + shift_x + direction.x * length, origin.y + shift_y + direction.y * length);
+            ctx.stroke();
+        }
+    }
+    ctx.restore();
+}
+
+pub struct Missile {
+    pub position: Vector,
+    pub velocity: Vector,
+    heading: f64,
+    lifetime: i32,
+}
+
+impl Missile {
+    pub fn new(origin: &Vector, heading: f64) -> Self {
+        Missile {
+            position: Vector { x: origin.x + 20.0 * heading.sin(), y: origin.y - 20.0 * heading.cos() },
+            velocity: Vector { x: MISSILE_SPEED * heading.sin(), y: -MISSILE_SPEED * heading.cos() },
+            heading,
+            lifetime: 150,
+        }
+    }
+
+    // Steers toward the nearest rock (through the screen edges if shorter)
+    pub fn update(&mut self, asteroids: &[Asteroid]) -> bool {
+        let target = asteroids
+            .iter()
+            .map(|asteroid| wrap_delta(&self.position, &asteroid.position))
+            .min_by(|a, b| (a.x * a.x + a.y * a.y).total_cmp(&(b.x * b.x + b.y * b.y)));
+        if let Some(delta) = target {
+            let desired = delta.x.atan2(-delta.y);
+            let difference = (desired - self.heading + PI).rem_euclid(2.0 * PI) - PI;
+            self.heading += difference.clamp(-MISSILE_TURN_RATE, MISSILE_TURN_RATE);
+        }
+
+        self.velocity = Vector { x: MISSILE_SPEED * self.heading.sin(), y: -MISSILE_SPEED * self.heading.cos() };
+        self.position.x = (self.position.x + self.velocity.x).rem_euclid(WORLD_WIDTH);
+        self.position.y = (self.position.y + self.velocity.y).rem_euclid(WORLD_HEIGHT);
+        self.lifetime -= 1;
+        self.lifetime > 0
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+        ctx.save();
+        ctx.translate(self.position.x, self.position.y).unwrap();
+        ctx.rotate(self.heading).unwrap();
+        ctx.set_stroke_style_str("white");
+        ctx.set_line_width(theme.line_scale);
+        ctx.begin_path();
+        ctx.move_to(0.0, -6.0);
+        ctx.line_to(3.0, 4.0);
+        ctx.line_to(-3.0, 4.0);
+        ctx.close_path();
+        ctx.stroke();
+
+        // Exhaust
+        ctx.set_stroke_style_str(theme.highlight);
+        ctx.begin_path();
+        ctx.move_to(0.0, 5.0);
+        ctx.line_to(0.0, 10.0);
+        ctx.stroke();
+        ctx.restore();
+    }
+}
+
+pub struct Mine {
+    pub position: Vector,
+    arm_timer: i32,
+    lifetime: i32,
+}
+
+impl Mine {
+    // Dropped just behind the ship
+    pub fn new(origin: &Vector, heading: f64) -> Self {
+        Mine {
+            position: Vector {
+                x: (origin.x - 24.0 * heading.sin()).rem_euclid(WORLD_WIDTH),
+                y: (origin.y + 24.0 * heading.cos()).rem_euclid(WORLD_HEIGHT),
+            },
+            arm_timer: MINE_ARM_FRAMES,
+            lifetime: MINE_LIFETIME,
+        }
+    }
+
+    pub fn armed(&self) -> bool {
+        self.arm_timer <= 0
+    }
+
+    pub fn update(&mut self) -> bool {
+        self.arm_timer -= 1;
+        self.lifetime -= 1;
+        self.lifetime > 0
+    }
+
+    pub fn triggered_by(&self, asteroid: &Asteroid) -> bool {
+        let delta = wrap_delta(&self.position, &asteroid.position);
+        self.armed() && (delta.x * delta.x + delta.y * delta.y).sqrt() < asteroid.size + MINE_TRIGGER_DISTANCE
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+        ctx.save();
+        ctx.translate(self.position.x, self.position.y).unwrap();
+        ctx.set_stroke_style_str(if self.armed() { theme.special } else { "white" });
+        ctx.set_line_width(theme.line_scale);
+        ctx.begin_path();
+        ctx.arc(0.0, 0.0, 5.0, 0.0, 2.0 * PI).unwrap();
+        for spike in 0..4 {
+            let angle = spike as f64 * PI / 2.0 + PI / 4.0;
+            ctx.move_to(5.0 * angle.cos(), 5.0 * angle.sin());
+            ctx.line_to(9.0 * angle.cos(), 9.0 * angle.sin());
+        }
+        ctx.stroke();
+        ctx.restore();
+    }
+}
+
+// Expanding ring left by a mine going off
+pub struct Blast {
+    position: Vector,
+    timer: i32,
+}
+
+const BLAST_FRAMES: i32 = 20;
+
+impl Blast {
+    pub fn new(position: Vector) -> Self {
+        Blast { position, timer: BLAST_FRAMES }
+    }
+
+    pub fn update(&mut self) -> bool {
+        self.timer -= 1;
+        self.timer > 0
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, theme: &Theme) {
+        let progress = 1.0 - self.timer as f64 / BLAST_FRAMES as f64;
+        ctx.save();
+        ctx.set_global_alpha(1.0 - progress);
+        ctx.set_stroke_style_str(theme.special);
+        ctx.set_line_width(2.0 * theme.line_scale);
+        ctx.begin_path();
+        ctx.arc(self.position.x, self.position.y, MINE_BLAST_RADIUS * progress.max(0.1), 0.0, 2.0 * PI).unwrap();
+        ctx.stroke();
+        ctx.restore();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fires the current weapon whenever it's ready, for `frames` frames,
+    // and returns how many shots went off
+    fn hold_fire(weapon: &mut WeaponState, settings: &WeaponSettings, frames: i32) -> i32 {
+        let mut shots = 0;
+        for _ in 0..frames {
+            if weapon.ready(settings, 0) {
+                weapon.fired(settings, false);
+                shots += 1;
+            }
+            weapon.tick(settings);
+        }
+        shots
+    }
+
+    #[test]
+    fn cooldown_spaces_out_shots() {
+        let settings = WeaponSettings::new();
+        let mut weapon = WeaponState::new();
+        assert!(weapon.ready(&settings, 0));
+        weapon.fired(&settings, false);
+        for _ in 0..settings.cooldown_frames {
+            assert!(!weapon.ready(&settings, 0));
+            weapon.tick(&settings);
+        }
+        assert!(weapon.ready(&settings, 0));
+
+        // Rapid fire halves it
+        weapon.fired(&settings, true);
+        for _ in 0..settings.cooldown_frames / 2 {
+            weapon.tick(&settings);
+        }
+        assert!(weapon.ready(&settings, 0));
+    }
+
+    #[test]
+    fn each_weapon_keeps_its_own_cooldown() {
+        let settings = WeaponSettings::new();
+        let mut weapon = WeaponState::new();
+        weapon.select(WeaponKind::Missiles);
+        weapon.fired(&settings, false);
+        weapon.select(WeaponKind::Blaster);
+        assert!(weapon.ready(&settings, 0));
+        weapon.select(WeaponKind::Missiles);
+        assert!(!weapon.ready(&settings, 0));
+    }
+
+    #[test]
+    fn ammo_runs_out_and_restocks() {
+        let settings = WeaponSettings::new();
+        let mut weapon = WeaponState::new();
+        assert_eq!(weapon.ammo(WeaponKind::Blaster), None);
+        weapon.select(WeaponKind::Mines);
+        assert_eq!(hold_fire(&mut weapon, &settings, 1000), 6);
+        assert_eq!(weapon.ammo(WeaponKind::Mines), Some(0));
+        assert!(!weapon.ready(&settings, 0));
+
+        // Cycling skips the empty launcher
+        weapon.select(WeaponKind::Missiles);
+        weapon.cycle(1);
+        assert!(weapon.current == WeaponKind::Shotgun);
+        weapon.cycle(-1);
+        assert!(weapon.current == WeaponKind::Missiles);
+
+        weapon.restock();
+        assert_eq!(weapon.ammo(WeaponKind::Mines), WeaponKind::Mines.max_ammo());
+    }
+
+    #[test]
+    fn laser_spends_ammo_for_every_frame_of_beam() {
+        let settings = WeaponSettings::new();
+        let mut weapon = WeaponState::new();
+        weapon.select(WeaponKind::Laser);
+        weapon.fired(&settings, false);
+        assert!(weapon.beam_active());
+        for _ in 0..LASER_BEAM_FRAMES {
+            weapon.tick(&settings);
+        }
+        assert!(!weapon.beam_active());
+        assert_eq!(weapon.ammo(WeaponKind::Laser), Some(300 - LASER_BEAM_FRAMES));
+    }
+
+    #[test]
+    fn bullet_limit_only_holds_back_bullet_weapons() {
+        let mut settings = WeaponSettings::new();
+        let mut weapon = WeaponState::new();
+        assert!(!weapon.ready(&settings, settings.max_bullets));
+        weapon.select(WeaponKind::Shotgun);
+        assert!(!weapon.ready(&settings, settings.max_bullets));
+        weapon.select(WeaponKind::Missiles);
+        assert!(weapon.ready(&settings, settings.max_bullets));
+
+        settings.max_bullets = 0;
+        weapon.select(WeaponKind::Blaster);
+        assert!(weapon.ready(&settings, 1000));
+    }
+
+    #[test]
+    fn overheated_weapon_waits_until_it_cools() {
+        let mut settings = WeaponSettings::new();
+        settings.overheat = true;
+        let mut weapon = WeaponState::new();
+        let mut shots = 0;
+        while !weapon.fired(&settings, false) {
+            shots += 1;
+            assert!(shots < 100);
+        }
+        assert!(weapon.overheated);
+        assert_eq!(weapon.heat, MAX_HEAT);
+
+        while weapon.overheated {
+            assert!(!weapon.ready(&settings, 0));
+            weapon.tick(&settings);
+        }
+        // Recovered on the first frame it was cool enough
+        assert!(weapon.heat <= settings.recover_heat);
+        assert!(weapon.heat > settings.recover_heat - settings.cooling_per_frame);
+        assert!(weapon.ready(&settings, 0));
+    }
+}